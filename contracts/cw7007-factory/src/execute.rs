@@ -0,0 +1,222 @@
+use cosmwasm_std::{
+    to_json_binary, Api, BankMsg, DepsMut, Env, MessageInfo, Reply, Response, StdError, StdResult,
+    SubMsg, WasmMsg,
+};
+use cw7007::msg::InstantiateMsg as Cw7007InstantiateMsg;
+use cw_utils::parse_reply_instantiate_data;
+
+use crate::error::ContractError;
+use crate::msg::{CreationFee, ExecuteMsg, InstantiateMsg};
+use crate::state::{Collection, Config, Cw7007Factory, PendingCollection};
+
+/// Reply to the instantiation of a new collection
+pub const INSTANTIATE_REPLY_ID: u64 = 1;
+
+/// Make sure a creation fee can be paid before storing it
+pub fn validate_creation_fee(api: &dyn Api, fee: &CreationFee) -> StdResult<()> {
+    if fee.amount.amount.is_zero() {
+        return Err(StdError::generic_err("creation fee must not be zero."));
+    }
+    api.addr_validate(&fee.recipient)?;
+    Ok(())
+}
+
+impl<'a> Cw7007Factory<'a> {
+    pub fn instantiate(
+        &self,
+        deps: DepsMut,
+        _env: Env,
+        info: MessageInfo,
+        msg: InstantiateMsg,
+    ) -> Result<Response, ContractError> {
+        cw_ownable::initialize_owner(deps.storage, deps.api, Some(info.sender.as_str()))?;
+
+        if let Some(fee) = &msg.creation_fee {
+            validate_creation_fee(deps.api, fee)?;
+        }
+        self.config.save(
+            deps.storage,
+            &Config {
+                cw7007_code_id: msg.cw7007_code_id,
+                creation_fee: msg.creation_fee,
+            },
+        )?;
+
+        Ok(Response::new()
+            .add_attribute("action", "instantiate")
+            .add_attribute("owner", info.sender))
+    }
+
+    pub fn execute(
+        &self,
+        deps: DepsMut,
+        env: Env,
+        info: MessageInfo,
+        msg: ExecuteMsg,
+    ) -> Result<Response, ContractError> {
+        match msg {
+            ExecuteMsg::CreateCollection {
+                name,
+                symbol,
+                minter,
+                prompt,
+            } => self.create_collection(deps, info, name, symbol, minter, prompt),
+            ExecuteMsg::UpdateCodeId { cw7007_code_id } => {
+                self.update_code_id(deps, info, cw7007_code_id)
+            }
+            ExecuteMsg::UpdateCreationFee { creation_fee } => {
+                self.update_creation_fee(deps, info, creation_fee)
+            }
+            ExecuteMsg::UpdateOwnership(action) => {
+                let ownership =
+                    cw_ownable::update_ownership(deps, &env.block, &info.sender, action)?;
+                Ok(Response::new().add_attributes(ownership.into_attributes()))
+            }
+        }
+    }
+
+    pub fn create_collection(
+        &self,
+        deps: DepsMut,
+        info: MessageInfo,
+        name: String,
+        symbol: String,
+        minter: String,
+        prompt: String,
+    ) -> Result<Response, ContractError> {
+        let config = self.config.load(deps.storage)?;
+
+        let mut res = Response::new();
+        match config.creation_fee {
+            Some(fee) => {
+                if info.funds != [fee.amount.clone()] {
+                    return Err(ContractError::Std(StdError::generic_err(format!(
+                        "creation fee is {}.",
+                        fee.amount
+                    ))));
+                }
+                res = res.add_message(BankMsg::Send {
+                    to_address: fee.recipient,
+                    amount: info.funds,
+                });
+            }
+            None => {
+                cw_utils::nonpayable(&info)?;
+            }
+        }
+
+        deps.api.addr_validate(&minter)?;
+        if prompt.is_empty() {
+            return Err(ContractError::Std(StdError::generic_err(
+                "prompt must not be empty.",
+            )));
+        }
+
+        let instantiate = WasmMsg::Instantiate {
+            admin: Some(info.sender.to_string()),
+            code_id: config.cw7007_code_id,
+            msg: to_json_binary(&Cw7007InstantiateMsg {
+                name: name.clone(),
+                symbol: symbol.clone(),
+                minter,
+                prompt,
+                verifier: None,
+                optimistic: None,
+                request_timeout: None,
+                mint_fee: None,
+                staking: None,
+                assignment: None,
+                quorum: None,
+                reveal_window: None,
+                token_id_strategy: None,
+                prompt_template: None,
+                callback_gas_limit: None,
+            })?,
+            funds: vec![],
+            label: format!("cw7007 {}", symbol),
+        };
+        self.pending.save(
+            deps.storage,
+            &PendingCollection {
+                creator: info.sender.clone(),
+                name,
+                symbol,
+            },
+        )?;
+
+        Ok(res
+            .add_submessage(SubMsg::reply_on_success(instantiate, INSTANTIATE_REPLY_ID))
+            .add_attribute("action", "create_collection")
+            .add_attribute("creator", info.sender))
+    }
+
+    pub fn update_code_id(
+        &self,
+        deps: DepsMut,
+        info: MessageInfo,
+        cw7007_code_id: u64,
+    ) -> Result<Response, ContractError> {
+        cw_ownable::assert_owner(deps.storage, &info.sender)?;
+
+        self.config
+            .update(deps.storage, |mut config| -> StdResult<_> {
+                config.cw7007_code_id = cw7007_code_id;
+                Ok(config)
+            })?;
+
+        Ok(Response::new()
+            .add_attribute("action", "update_code_id")
+            .add_attribute("sender", info.sender)
+            .add_attribute("code_id", cw7007_code_id.to_string()))
+    }
+
+    pub fn update_creation_fee(
+        &self,
+        deps: DepsMut,
+        info: MessageInfo,
+        creation_fee: Option<CreationFee>,
+    ) -> Result<Response, ContractError> {
+        cw_ownable::assert_owner(deps.storage, &info.sender)?;
+
+        if let Some(fee) = &creation_fee {
+            validate_creation_fee(deps.api, fee)?;
+        }
+        self.config
+            .update(deps.storage, |mut config| -> StdResult<_> {
+                config.creation_fee = creation_fee;
+                Ok(config)
+            })?;
+
+        Ok(Response::new()
+            .add_attribute("action", "update_creation_fee")
+            .add_attribute("sender", info.sender))
+    }
+
+    /// Record a new collection once it is instantiated
+    pub fn reply(&self, deps: DepsMut, env: Env, msg: Reply) -> Result<Response, ContractError> {
+        if msg.id != INSTANTIATE_REPLY_ID {
+            return Err(ContractError::Std(StdError::generic_err(format!(
+                "unknown reply id {}.",
+                msg.id
+            ))));
+        }
+        let data = parse_reply_instantiate_data(msg)?;
+        let address = deps.api.addr_validate(&data.contract_address)?;
+
+        let pending = self.pending.load(deps.storage)?;
+        self.pending.remove(deps.storage);
+        self.collections.save(
+            deps.storage,
+            &address,
+            &Collection {
+                address: address.clone(),
+                creator: pending.creator,
+                name: pending.name,
+                symbol: pending.symbol,
+                created_at: env.block.time,
+            },
+        )?;
+
+        Ok(Response::new().add_attribute("collection", address))
+    }
+}
@@ -0,0 +1,52 @@
+mod error;
+mod execute;
+pub mod msg;
+#[cfg(test)]
+mod multitest;
+mod query;
+pub mod state;
+
+use cosmwasm_std::{Binary, Deps, DepsMut, Env, MessageInfo, Reply, Response, StdResult};
+pub use error::ContractError;
+use msg::{ExecuteMsg, InstantiateMsg, QueryMsg};
+use state::Cw7007Factory;
+
+// Version info for migration
+const CONTRACT_NAME: &str = "crates.io:cw7007-factory";
+const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
+
+pub mod entry {
+    use super::*;
+
+    #[cfg_attr(not(feature = "library"), cosmwasm_std::entry_point)]
+    pub fn instantiate(
+        deps: DepsMut,
+        env: Env,
+        info: MessageInfo,
+        msg: InstantiateMsg,
+    ) -> Result<Response, ContractError> {
+        cw2::set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+
+        Cw7007Factory::default().instantiate(deps, env, info, msg)
+    }
+
+    #[cfg_attr(not(feature = "library"), cosmwasm_std::entry_point)]
+    pub fn execute(
+        deps: DepsMut,
+        env: Env,
+        info: MessageInfo,
+        msg: ExecuteMsg,
+    ) -> Result<Response, ContractError> {
+        Cw7007Factory::default().execute(deps, env, info, msg)
+    }
+
+    #[cfg_attr(not(feature = "library"), cosmwasm_std::entry_point)]
+    pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
+        Cw7007Factory::default().query(deps, env, msg)
+    }
+
+    #[cfg_attr(not(feature = "library"), cosmwasm_std::entry_point)]
+    pub fn reply(deps: DepsMut, env: Env, msg: Reply) -> Result<Response, ContractError> {
+        Cw7007Factory::default().reply(deps, env, msg)
+    }
+}
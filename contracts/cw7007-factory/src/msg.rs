@@ -0,0 +1,76 @@
+use cosmwasm_schema::{cw_serde, QueryResponses};
+use cosmwasm_std::Coin;
+use cw_ownable::{cw_ownable_execute, cw_ownable_query};
+
+#[cw_serde]
+pub struct InstantiateMsg {
+    /// Code id of the cw7007 contract new collections are instantiated from
+    pub cw7007_code_id: u64,
+    /// Fee of a new collection. Creating a collection is free if unset
+    pub creation_fee: Option<CreationFee>,
+}
+
+#[cw_serde]
+pub struct CreationFee {
+    /// Exact funds to send along `CreateCollection`
+    pub amount: Coin,
+    /// Receives every fee as soon as it is paid
+    pub recipient: String,
+}
+
+#[cw_ownable_execute]
+#[cw_serde]
+pub enum ExecuteMsg {
+    /// Instantiate a new cw7007 collection, administered by the sender.
+    /// The creation fee has to be sent along, if any
+    CreateCollection {
+        /// Name of the NFT contract
+        name: String,
+        /// Symbol of the NFT contract
+        symbol: String,
+        /// Minter and owner of the collection
+        minter: String,
+        /// Prompt of the collection
+        prompt: String,
+    },
+
+    /// Instantiate the next collections from another code id.
+    /// Can only be called by the factory owner
+    UpdateCodeId { cw7007_code_id: u64 },
+
+    /// Update the creation fee, can only be called by the factory owner
+    UpdateCreationFee { creation_fee: Option<CreationFee> },
+}
+
+#[cw_ownable_query]
+#[cw_serde]
+#[derive(QueryResponses)]
+pub enum QueryMsg {
+    /// Return the code id and creation fee
+    #[returns(crate::state::Config)]
+    Config {},
+
+    /// Return a collection created by the factory
+    #[returns(crate::state::Collection)]
+    Collection { address: String },
+
+    /// List the collections created by the factory, by address
+    #[returns(CollectionsResponse)]
+    Collections {
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+
+    /// List the collections created by `creator`, by address
+    #[returns(CollectionsResponse)]
+    CollectionsByCreator {
+        creator: String,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+}
+
+#[cw_serde]
+pub struct CollectionsResponse {
+    pub collections: Vec<crate::state::Collection>,
+}
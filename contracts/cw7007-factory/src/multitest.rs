@@ -0,0 +1,175 @@
+use cosmwasm_std::{coin, coins, Addr, Empty};
+use cw7007::msg::{PromptInfoResponse, QueryMsg as Cw7007QueryMsg};
+use cw_multi_test::error::AnyResult;
+use cw_multi_test::{App, AppResponse, ContractWrapper, Executor};
+
+use crate::entry;
+use crate::msg::{CollectionsResponse, CreationFee, ExecuteMsg, InstantiateMsg, QueryMsg};
+use crate::state::Collection;
+
+const OWNER: &str = "owner";
+const TREASURY: &str = "treasury";
+const ALICE: &str = "alice";
+const BOB: &str = "bob";
+const DENOM: &str = "ucat";
+
+fn setup() -> (App, Addr) {
+    let mut app = App::new(|router, _, storage| {
+        for user in [ALICE, BOB] {
+            router
+                .bank
+                .init_balance(storage, &Addr::unchecked(user), coins(100, DENOM))
+                .unwrap();
+        }
+    });
+    let cw7007_code_id = app.store_code(Box::new(
+        ContractWrapper::new(
+            cw7007::entry::execute,
+            cw7007::entry::instantiate,
+            cw7007::entry::query,
+        )
+        .with_reply(cw7007::entry::reply),
+    ));
+    let factory_code_id = app.store_code(Box::new(
+        ContractWrapper::new(entry::execute, entry::instantiate, entry::query)
+            .with_reply(entry::reply),
+    ));
+    let factory = app
+        .instantiate_contract(
+            factory_code_id,
+            Addr::unchecked(OWNER),
+            &InstantiateMsg {
+                cw7007_code_id,
+                creation_fee: Some(CreationFee {
+                    amount: coin(10, DENOM),
+                    recipient: TREASURY.to_string(),
+                }),
+            },
+            &[],
+            "cw7007-factory",
+            None,
+        )
+        .unwrap();
+    (app, factory)
+}
+
+fn create(app: &mut App, factory: &Addr, creator: &str, fee: u128) -> AnyResult<AppResponse> {
+    let msg = ExecuteMsg::CreateCollection {
+        name: "Cats".to_string(),
+        symbol: "MEOW".to_string(),
+        minter: creator.to_string(),
+        prompt: "You are a cat.".to_string(),
+    };
+    let funds = match fee {
+        0 => vec![],
+        fee => coins(fee, DENOM),
+    };
+    app.execute_contract(Addr::unchecked(creator), factory.clone(), &msg, &funds)
+}
+
+fn balance(app: &App, user: &str) -> u128 {
+    app.wrap().query_balance(user, DENOM).unwrap().amount.u128()
+}
+
+fn collections_by_creator(app: &App, factory: &Addr, creator: &str) -> Vec<Collection> {
+    let res: CollectionsResponse = app
+        .wrap()
+        .query_wasm_smart(
+            factory,
+            &QueryMsg::CollectionsByCreator {
+                creator: creator.to_string(),
+                start_after: None,
+                limit: None,
+            },
+        )
+        .unwrap();
+    res.collections
+}
+
+#[test]
+fn create_collection() {
+    let (mut app, factory) = setup();
+
+    create(&mut app, &factory, ALICE, 0).unwrap_err();
+    create(&mut app, &factory, ALICE, 5).unwrap_err();
+    create(&mut app, &factory, ALICE, 10).unwrap();
+    assert_eq!(balance(&app, TREASURY), 10);
+    assert_eq!(balance(&app, ALICE), 90);
+
+    let collections = collections_by_creator(&app, &factory, ALICE);
+    assert_eq!(collections.len(), 1);
+    let collection = &collections[0];
+    assert_eq!(collection.creator, Addr::unchecked(ALICE));
+    assert_eq!(collection.symbol, "MEOW");
+
+    // the collection is live, administered by its creator
+    let res: PromptInfoResponse = app
+        .wrap()
+        .query_wasm_smart(&collection.address, &Cw7007QueryMsg::<Empty>::Prompt {})
+        .unwrap();
+    assert_eq!(res.prompt, "You are a cat.");
+    let info = app
+        .wrap()
+        .query_wasm_contract_info(&collection.address)
+        .unwrap();
+    assert_eq!(info.admin, Some(ALICE.to_string()));
+}
+
+#[test]
+fn collections_by_creator_pagination() {
+    let (mut app, factory) = setup();
+
+    for creator in [ALICE, BOB, ALICE] {
+        create(&mut app, &factory, creator, 10).unwrap();
+    }
+    assert_eq!(collections_by_creator(&app, &factory, BOB).len(), 1);
+    let alice = collections_by_creator(&app, &factory, ALICE);
+    assert_eq!(alice.len(), 2);
+
+    let res: CollectionsResponse = app
+        .wrap()
+        .query_wasm_smart(
+            &factory,
+            &QueryMsg::CollectionsByCreator {
+                creator: ALICE.to_string(),
+                start_after: Some(alice[0].address.to_string()),
+                limit: Some(1),
+            },
+        )
+        .unwrap();
+    assert_eq!(res.collections, vec![alice[1].clone()]);
+
+    let res: CollectionsResponse = app
+        .wrap()
+        .query_wasm_smart(
+            &factory,
+            &QueryMsg::Collections {
+                start_after: None,
+                limit: None,
+            },
+        )
+        .unwrap();
+    assert_eq!(res.collections.len(), 3);
+}
+
+#[test]
+fn owner_updates() {
+    let (mut app, factory) = setup();
+
+    let msg = ExecuteMsg::UpdateCreationFee { creation_fee: None };
+    app.execute_contract(Addr::unchecked(ALICE), factory.clone(), &msg, &[])
+        .unwrap_err();
+    app.execute_contract(Addr::unchecked(OWNER), factory.clone(), &msg, &[])
+        .unwrap();
+
+    // free once the fee is removed, and funds are refused
+    create(&mut app, &factory, ALICE, 10).unwrap_err();
+    create(&mut app, &factory, ALICE, 0).unwrap();
+
+    let msg = ExecuteMsg::UpdateCodeId { cw7007_code_id: 42 };
+    app.execute_contract(Addr::unchecked(ALICE), factory.clone(), &msg, &[])
+        .unwrap_err();
+    app.execute_contract(Addr::unchecked(OWNER), factory.clone(), &msg, &[])
+        .unwrap();
+    create(&mut app, &factory, ALICE, 0).unwrap_err();
+}
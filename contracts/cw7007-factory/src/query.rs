@@ -0,0 +1,84 @@
+use cosmwasm_std::{to_json_binary, Binary, Deps, Env, Order, StdResult};
+use cw_storage_plus::Bound;
+
+use crate::msg::{CollectionsResponse, QueryMsg};
+use crate::state::{Collection, Config, Cw7007Factory};
+
+const DEFAULT_LIMIT: u32 = 10;
+const MAX_LIMIT: u32 = 1000;
+
+impl<'a> Cw7007Factory<'a> {
+    pub fn query(&self, deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
+        match msg {
+            QueryMsg::Config {} => to_json_binary(&self.query_config(deps)?),
+            QueryMsg::Collection { address } => to_json_binary(&self.collection(deps, address)?),
+            QueryMsg::Collections { start_after, limit } => {
+                to_json_binary(&self.collections(deps, start_after, limit)?)
+            }
+            QueryMsg::CollectionsByCreator {
+                creator,
+                start_after,
+                limit,
+            } => to_json_binary(&self.collections_by_creator(deps, creator, start_after, limit)?),
+            QueryMsg::Ownership {} => to_json_binary(&cw_ownable::get_ownership(deps.storage)?),
+        }
+    }
+
+    fn query_config(&self, deps: Deps) -> StdResult<Config> {
+        self.config.load(deps.storage)
+    }
+
+    fn collection(&self, deps: Deps, address: String) -> StdResult<Collection> {
+        let address = deps.api.addr_validate(&address)?;
+        self.collections.load(deps.storage, &address)
+    }
+
+    fn collections(
+        &self,
+        deps: Deps,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    ) -> StdResult<CollectionsResponse> {
+        let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+        let start_after = start_after
+            .map(|address| deps.api.addr_validate(&address))
+            .transpose()?;
+        let start = start_after.as_ref().map(Bound::exclusive);
+
+        let collections = self
+            .collections
+            .range(deps.storage, start, None, Order::Ascending)
+            .take(limit)
+            .map(|item| item.map(|(_, collection)| collection))
+            .collect::<StdResult<_>>()?;
+
+        Ok(CollectionsResponse { collections })
+    }
+
+    fn collections_by_creator(
+        &self,
+        deps: Deps,
+        creator: String,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    ) -> StdResult<CollectionsResponse> {
+        let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+        let creator = deps.api.addr_validate(&creator)?;
+        let start_after = start_after
+            .map(|address| deps.api.addr_validate(&address))
+            .transpose()?;
+        let start = start_after.as_ref().map(Bound::exclusive);
+
+        let collections = self
+            .collections
+            .idx
+            .creator
+            .prefix(creator)
+            .range(deps.storage, start, None, Order::Ascending)
+            .take(limit)
+            .map(|item| item.map(|(_, collection)| collection))
+            .collect::<StdResult<_>>()?;
+
+        Ok(CollectionsResponse { collections })
+    }
+}
@@ -0,0 +1,70 @@
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::{Addr, Timestamp};
+use cw_storage_plus::{Index, IndexList, IndexedMap, Item, MultiIndex};
+
+use crate::msg::CreationFee;
+
+#[cw_serde]
+pub struct Config {
+    pub cw7007_code_id: u64,
+    pub creation_fee: Option<CreationFee>,
+}
+
+/// Collection instantiated by the factory
+#[cw_serde]
+pub struct Collection {
+    pub address: Addr,
+    /// Sender of `CreateCollection`, admin of the collection
+    pub creator: Addr,
+    pub name: String,
+    pub symbol: String,
+    pub created_at: Timestamp,
+}
+
+/// Collection waiting for the reply of its instantiation
+#[cw_serde]
+pub struct PendingCollection {
+    pub creator: Addr,
+    pub name: String,
+    pub symbol: String,
+}
+
+pub struct CollectionIndexes<'a> {
+    pub creator: MultiIndex<'a, Addr, Collection, &'a Addr>,
+}
+
+impl<'a> IndexList<Collection> for CollectionIndexes<'a> {
+    fn get_indexes(&'_ self) -> Box<dyn Iterator<Item = &'_ dyn Index<Collection>> + '_> {
+        let v: Vec<&dyn Index<Collection>> = vec![&self.creator];
+        Box::new(v.into_iter())
+    }
+}
+
+pub struct Cw7007Factory<'a> {
+    pub config: Item<'a, Config>,
+    pub collections: IndexedMap<'a, &'a Addr, Collection, CollectionIndexes<'a>>,
+    pub pending: Item<'a, PendingCollection>,
+}
+
+impl Default for Cw7007Factory<'static> {
+    fn default() -> Self {
+        Self::new("collections")
+    }
+}
+
+impl<'a> Cw7007Factory<'a> {
+    fn new(collections_key: &'a str) -> Self {
+        let indexes = CollectionIndexes {
+            creator: MultiIndex::new(
+                |_, c: &Collection| c.creator.clone(),
+                collections_key,
+                "collections__creator",
+            ),
+        };
+        Self {
+            config: Item::new("config"),
+            collections: IndexedMap::new(collections_key, indexes),
+            pending: Item::new("pending"),
+        }
+    }
+}
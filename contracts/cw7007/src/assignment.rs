@@ -0,0 +1,294 @@
+use cosmwasm_std::{
+    Addr, CustomMsg, DepsMut, Env, MessageInfo, Response, StdError, StdResult, Storage, Uint128,
+};
+use cw721_base::ContractError;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+use crate::model::generation_seed;
+use crate::msg::{AssignmentConfig, AssignmentStrategy};
+use crate::state::{AssignmentWeight, Cw7007Contract, Request, RequestStatus};
+
+/// Make sure an assignment config is consistent before storing it
+pub fn validate_assignment(config: &AssignmentConfig, staking: bool) -> StdResult<()> {
+    if config.strategy == (AssignmentStrategy::StakeWeighted {}) && !staking {
+        return Err(StdError::generic_err(
+            "stake weighted assignment requires staking.",
+        ));
+    }
+    Ok(())
+}
+
+impl<'a, T, C, E, Q> Cw7007Contract<'a, T, C, E, Q>
+where
+    T: Serialize + DeserializeOwned + Clone,
+    C: CustomMsg,
+    E: CustomMsg,
+    Q: CustomMsg,
+{
+    pub fn reassign_request(
+        &self,
+        deps: DepsMut,
+        env: Env,
+        info: MessageInfo,
+        token_id: String,
+    ) -> Result<Response<C>, ContractError> {
+        let mut request = self.requests.load(deps.storage, &token_id)?;
+        if request.status != RequestStatus::Pending {
+            return Err(ContractError::Std(StdError::generic_err(
+                "request is not pending.",
+            )));
+        }
+        match request.reassign_at {
+            Some(reassign_at) if reassign_at.is_expired(&env.block) => {}
+            _ => {
+                return Err(ContractError::Std(StdError::generic_err(
+                    "request can not be reassigned yet.",
+                )));
+            }
+        }
+
+        let previous = request.assignee.take();
+        self.assign(deps.storage, &env, &mut request, previous.as_ref())?;
+        self.requests.save(deps.storage, &token_id, &request)?;
+
+        Ok(Response::new()
+            .add_attribute("action", "reassign_request")
+            .add_attribute("sender", info.sender)
+            .add_attribute("token_id", token_id)
+            .add_attribute(
+                "assignee",
+                request
+                    .assignee
+                    .map(String::from)
+                    .unwrap_or_else(|| "none".to_string()),
+            ))
+    }
+
+    /// Pick the oracle of `request` with the configured strategy, leaving `exclude` out.
+    /// The request is left open to any oracle if assignment is disabled
+    /// or no oracle is eligible
+    pub fn assign(
+        &self,
+        storage: &mut dyn Storage,
+        env: &Env,
+        request: &mut Request,
+        exclude: Option<&Addr>,
+    ) -> StdResult<()> {
+        let config = match self.assignment.load(storage)? {
+            Some(config) => config,
+            None => return Ok(()),
+        };
+        let count = self.assignable_count.may_load(storage)?.unwrap_or_default();
+        // the excluded oracle is left out of the draw, then skipped over
+        let excluded = match exclude {
+            Some(oracle) => self.assignable_positions.may_load(storage, oracle)?,
+            None => None,
+        };
+        let excluded = match excluded {
+            Some(position) => Some((position, self.position_weight(storage, position)?)),
+            None => None,
+        };
+        let eligible = self
+            .prefix_weight(storage, count)?
+            .minus(&excluded.map(|(_, weight)| weight).unwrap_or_default());
+        if eligible.oracles == 0 {
+            request.assignee = None;
+            request.reassign_at = None;
+            return Ok(());
+        }
+
+        let seed = Uint128::from(generation_seed(env, &request.token_id));
+        let (by_stake, point) = match config.strategy {
+            AssignmentStrategy::RoundRobin {} => {
+                // the first eligible oracle after the last one picked, wrapping around
+                let next = self
+                    .assignment_cursor
+                    .may_load(storage)?
+                    .map_or(0, |cursor| cursor + 1)
+                    % count;
+                let mut before = self.prefix_weight(storage, next)?;
+                if let Some((position, weight)) = &excluded {
+                    if *position < next {
+                        before = before.minus(weight);
+                    }
+                }
+                (false, Uint128::from(before.oracles % eligible.oracles))
+            }
+            AssignmentStrategy::StakeWeighted {} if !eligible.stake.is_zero() => {
+                (true, seed % eligible.stake)
+            }
+            // without any stake bonded, stake weighted falls back to uniform
+            _ => (false, seed % Uint128::from(eligible.oracles)),
+        };
+        let measure = |weight: &AssignmentWeight| match by_stake {
+            true => weight.stake,
+            false => Uint128::from(weight.oracles),
+        };
+        let mut point = point;
+        if let Some((position, weight)) = &excluded {
+            if point >= measure(&self.prefix_weight(storage, *position)?) {
+                point += measure(weight);
+            }
+        }
+        let position = self.find_position(storage, count, point, measure)?;
+        let oracle = self.assignable_oracles.load(storage, position)?;
+
+        self.assignment_cursor.save(storage, &position)?;
+        request.reassign_at = Some(config.timeout.after(&env.block));
+        request.assignee = Some(oracle);
+        Ok(())
+    }
+
+    /// Add `oracle` to the oracles assignment picks from, or take it out.
+    /// Nothing changes if it already is or isn't part of them
+    pub fn set_assignable(
+        &self,
+        storage: &mut dyn Storage,
+        oracle: &Addr,
+        assignable: bool,
+    ) -> StdResult<()> {
+        let count = self.assignable_count.may_load(storage)?.unwrap_or_default();
+        match (
+            self.assignable_positions.may_load(storage, oracle)?,
+            assignable,
+        ) {
+            (None, true) => {
+                // the new node covers the positions below it that its index range spans
+                let index = count + 1;
+                let weight = self.assignment_weight(storage, oracle)?;
+                let covered = self
+                    .prefix_weight(storage, count)?
+                    .minus(&self.prefix_weight(storage, index - lowest_bit(index))?);
+                self.assignment_tree
+                    .save(storage, index, &weight.plus(&covered))?;
+                self.assignable_oracles.save(storage, count, oracle)?;
+                self.assignable_positions.save(storage, oracle, &count)?;
+                self.assignable_count.save(storage, &index)
+            }
+            (Some(position), false) => {
+                // the last oracle moves into the freed position to keep them packed
+                let last = count - 1;
+                if position != last {
+                    let moved = self.assignable_oracles.load(storage, last)?;
+                    let old = self.position_weight(storage, position)?;
+                    let new = self.position_weight(storage, last)?;
+                    self.update_tree(storage, count, position, &old, &new)?;
+                    self.assignable_oracles.save(storage, position, &moved)?;
+                    self.assignable_positions.save(storage, &moved, &position)?;
+                }
+                // no node below the last one covers its position
+                self.assignment_tree.remove(storage, count);
+                self.assignable_oracles.remove(storage, last);
+                self.assignable_positions.remove(storage, oracle);
+                self.assignable_count.save(storage, &last)
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Bring the weight of `oracle` in line with its stake, after a bond, unbond or slash
+    pub fn update_assignment_weight(
+        &self,
+        storage: &mut dyn Storage,
+        oracle: &Addr,
+    ) -> StdResult<()> {
+        if let Some(position) = self.assignable_positions.may_load(storage, oracle)? {
+            let count = self.assignable_count.load(storage)?;
+            let old = self.position_weight(storage, position)?;
+            let new = self.assignment_weight(storage, oracle)?;
+            if old != new {
+                self.update_tree(storage, count, position, &old, &new)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Weight of `oracle` in the draw, with its bonded stake.
+    /// Oracles below the minimum stake are not eligible
+    fn assignment_weight(
+        &self,
+        storage: &dyn Storage,
+        oracle: &Addr,
+    ) -> StdResult<AssignmentWeight> {
+        if !self.has_min_stake(storage, oracle)? {
+            return Ok(AssignmentWeight::default());
+        }
+        Ok(AssignmentWeight {
+            oracles: 1,
+            stake: self
+                .stakes
+                .may_load(storage, oracle)?
+                .unwrap_or_default()
+                .bonded,
+        })
+    }
+
+    /// Total weight of the positions below `end`
+    fn prefix_weight(&self, storage: &dyn Storage, end: u32) -> StdResult<AssignmentWeight> {
+        let mut total = AssignmentWeight::default();
+        let mut index = end;
+        while index > 0 {
+            total = total.plus(&self.assignment_tree.load(storage, index)?);
+            index -= lowest_bit(index);
+        }
+        Ok(total)
+    }
+
+    fn position_weight(&self, storage: &dyn Storage, position: u32) -> StdResult<AssignmentWeight> {
+        Ok(self
+            .prefix_weight(storage, position + 1)?
+            .minus(&self.prefix_weight(storage, position)?))
+    }
+
+    /// Replace the `old` weight of `position` with `new` in every node covering it
+    fn update_tree(
+        &self,
+        storage: &mut dyn Storage,
+        count: u32,
+        position: u32,
+        old: &AssignmentWeight,
+        new: &AssignmentWeight,
+    ) -> StdResult<()> {
+        let mut index = position + 1;
+        while index <= count {
+            let node = self.assignment_tree.load(storage, index)?;
+            self.assignment_tree
+                .save(storage, index, &node.minus(old).plus(new))?;
+            index += lowest_bit(index);
+        }
+        Ok(())
+    }
+
+    /// Position whose weight range holds `point`, by descending the tree
+    fn find_position(
+        &self,
+        storage: &dyn Storage,
+        count: u32,
+        mut point: Uint128,
+        measure: impl Fn(&AssignmentWeight) -> Uint128,
+    ) -> StdResult<u32> {
+        let mut position = 0;
+        let mut step = match count {
+            0 => 0,
+            count => 1 << (u32::BITS - 1 - count.leading_zeros()),
+        };
+        while step > 0 {
+            let next = position + step;
+            if next <= count {
+                let weight = measure(&self.assignment_tree.load(storage, next)?);
+                if weight <= point {
+                    position = next;
+                    point -= weight;
+                }
+            }
+            step >>= 1;
+        }
+        Ok(position)
+    }
+}
+
+/// Size of the range of positions a tree node covers
+fn lowest_bit(index: u32) -> u32 {
+    index & index.wrapping_neg()
+}
@@ -1,7 +1,7 @@
 use cosmwasm_schema::write_api;
 
 use cosmwasm_std::Empty;
-use cw7007::msg::InstantiateMsg;
+use cw7007::msg::{InstantiateMsg, MigrateMsg};
 use cw721_base::Extension;
 
 pub type ExecuteMsg = cw7007::msg::ExecuteMsg<Extension, Empty>;
@@ -12,5 +12,6 @@ fn main() {
         instantiate: InstantiateMsg,
         execute: ExecuteMsg,
         query: QueryMsg,
+        migrate: MigrateMsg,
     }
 }
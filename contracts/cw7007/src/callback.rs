@@ -0,0 +1,72 @@
+use cosmwasm_std::{
+    CustomMsg, DepsMut, Env, Reply, Response, StdError, StdResult, Storage, SubMsg, SubMsgResult,
+};
+use cw721_base::ContractError;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+use crate::msg::Cw7007ReceiveMsg;
+use crate::state::{Cw7007Contract, Request};
+
+/// Gas a callback can use unless the collection sets its own limit
+pub const DEFAULT_CALLBACK_GAS_LIMIT: u64 = 500_000;
+
+impl<'a, T, C, E, Q> Cw7007Contract<'a, T, C, E, Q>
+where
+    T: Serialize + DeserializeOwned + Clone,
+    C: CustomMsg,
+    E: CustomMsg,
+    Q: CustomMsg,
+{
+    /// Submessage notifying the callback contract of `request`, if any.
+    /// Errors are caught in `reply` and gas is capped,
+    /// so that the callback can't revert the response
+    pub fn callback_msg(
+        &self,
+        storage: &mut dyn Storage,
+        request: &Request,
+        output: &str,
+    ) -> StdResult<Option<SubMsg<C>>> {
+        let callback = match &request.callback {
+            Some(callback) => callback,
+            None => return Ok(None),
+        };
+        // every callback gets its own reply id, so that nested ones can't mix up tokens
+        let reply_id = self.callback_count.may_load(storage)?.unwrap_or_default() + 1;
+        self.callback_count.save(storage, &reply_id)?;
+        self.callback_tokens
+            .save(storage, reply_id, &request.token_id)?;
+
+        let msg = Cw7007ReceiveMsg {
+            token_id: request.token_id.clone(),
+            output: output.to_string(),
+            payload: callback.payload.clone(),
+        }
+        .into_cosmos_msg(&callback.contract)?;
+        let gas_limit = self.callback_gas_limit.load(storage)?;
+        Ok(Some(
+            SubMsg::reply_always(msg, reply_id).with_gas_limit(gas_limit),
+        ))
+    }
+
+    pub fn reply(
+        &self,
+        deps: DepsMut,
+        _env: Env,
+        msg: Reply,
+    ) -> Result<Response<C>, ContractError> {
+        let token_id = self
+            .callback_tokens
+            .may_load(deps.storage, msg.id)?
+            .ok_or_else(|| StdError::generic_err(format!("unknown reply id {}.", msg.id)))?;
+        self.callback_tokens.remove(deps.storage, msg.id);
+
+        match msg.result {
+            SubMsgResult::Ok(_) => Ok(Response::new()),
+            SubMsgResult::Err(err) => Ok(Response::new()
+                .add_attribute("action", "callback_failed")
+                .add_attribute("token_id", token_id)
+                .add_attribute("error", err)),
+        }
+    }
+}
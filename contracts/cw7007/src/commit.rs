@@ -0,0 +1,141 @@
+use cosmwasm_std::{
+    Addr, CustomMsg, DepsMut, Env, HexBinary, MessageInfo, Order, Response, StdError, StdResult,
+    Storage,
+};
+use cw721_base::ContractError;
+
+use crate::msg::{Attestation, Generation};
+use crate::state::{Commitment, Cw7007Contract, Extension};
+use crate::verifier::commitment_hash;
+
+impl<'a, C, E, Q> Cw7007Contract<'a, Extension, C, E, Q>
+where
+    C: CustomMsg,
+    E: CustomMsg,
+    Q: CustomMsg,
+{
+    pub fn commit_response(
+        &self,
+        deps: DepsMut,
+        env: Env,
+        info: MessageInfo,
+        token_id: String,
+        hash: HexBinary,
+    ) -> Result<Response<C>, ContractError> {
+        let reveal_window = self
+            .reveal_window
+            .load(deps.storage)?
+            .ok_or_else(|| StdError::generic_err("commit-reveal is not enabled."))?;
+        self.assert_oracle(deps.storage, &info.sender)?;
+        if self.quorum.load(deps.storage)?.is_some() {
+            return Err(ContractError::Std(StdError::generic_err(
+                "outputs go through SubmitOutputHash in quorum mode.",
+            )));
+        }
+
+        self.load_open_request(deps.storage, &env, &token_id)?
+            .assert_assignee(&info.sender)?;
+        if hash.len() != 32 {
+            return Err(ContractError::Std(StdError::generic_err(
+                "commitment must be a sha256 hash.",
+            )));
+        }
+        if let Some(commitment) = self
+            .commitments
+            .may_load(deps.storage, (&token_id, &info.sender))?
+        {
+            if !commitment.reveal_by.is_expired(&env.block) {
+                return Err(ContractError::Std(StdError::generic_err(
+                    "output is already committed.",
+                )));
+            }
+        }
+
+        let reveal_by = reveal_window.after(&env.block);
+        self.commitments.save(
+            deps.storage,
+            (&token_id, &info.sender),
+            &Commitment {
+                hash,
+                committed_at: env.block.height,
+                reveal_by,
+            },
+        )?;
+
+        Ok(Response::new()
+            .add_attribute("action", "commit_response")
+            .add_attribute("sender", info.sender)
+            .add_attribute("token_id", token_id)
+            .add_attribute("reveal_by", reveal_by.to_string()))
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    pub fn reveal_response(
+        &self,
+        deps: DepsMut,
+        env: Env,
+        info: MessageInfo,
+        token_id: String,
+        output: String,
+        salt: String,
+        attestation: Option<Attestation>,
+        generation: Option<Generation>,
+    ) -> Result<Response<C>, ContractError> {
+        self.assert_oracle(deps.storage, &info.sender)?;
+
+        let commitment = self
+            .commitments
+            .may_load(deps.storage, (&token_id, &info.sender))?
+            .ok_or_else(|| StdError::generic_err("no output is committed."))?;
+        if commitment.reveal_by.is_expired(&env.block) {
+            return Err(ContractError::Std(StdError::generic_err(
+                "reveal window is over.",
+            )));
+        }
+        // revealing in the commit block would leave the output exposed like a plain response
+        if env.block.height <= commitment.committed_at {
+            return Err(ContractError::Std(StdError::generic_err(
+                "reveal must come after the commit block.",
+            )));
+        }
+        let hash = commitment_hash(&token_id, &output, &salt, info.sender.as_str());
+        if commitment.hash.as_slice() != hash {
+            return Err(ContractError::Std(StdError::generic_err(
+                "output does not match the commitment.",
+            )));
+        }
+        self.commitments
+            .remove(deps.storage, (&token_id, &info.sender));
+
+        self.accept_output(
+            deps,
+            &env,
+            &info.sender,
+            &token_id,
+            output,
+            attestation,
+            generation,
+        )
+    }
+
+    /// Whether an oracle other than `oracle` holds a commitment that can still be revealed
+    pub fn has_open_commitment(
+        &self,
+        storage: &dyn Storage,
+        env: &Env,
+        token_id: &str,
+        oracle: &Addr,
+    ) -> StdResult<bool> {
+        for item in self
+            .commitments
+            .prefix(token_id)
+            .range(storage, None, None, Order::Ascending)
+        {
+            let (committer, commitment) = item?;
+            if committer != *oracle && !commitment.reveal_by.is_expired(&env.block) {
+                return Ok(true);
+            }
+        }
+        Ok(false)
+    }
+}
@@ -2,11 +2,25 @@ use cw721_base::state::TokenInfo;
 use serde::de::DeserializeOwned;
 use serde::Serialize;
 
-use cosmwasm_std::{CustomMsg, DepsMut, Env, MessageInfo, Response, StdResult};
+use cosmwasm_std::{
+    to_json_string, Addr, Binary, CustomMsg, Deps, DepsMut, Empty, Env, MessageInfo, Response,
+    StdError, StdResult, Storage,
+};
 
-use crate::msg::{ExecuteMsg, InstantiateMsg, PromptInfoResponse, RequestIdsResponse};
-use crate::state::{Cw7007Contract, Extension};
+use crate::assignment::validate_assignment;
+use crate::callback::DEFAULT_CALLBACK_GAS_LIMIT;
+use crate::model::generation_traits;
+use crate::msg::{
+    Attestation, Cw7007HookMsg, ExecuteMsg, Generation, InstantiateMsg, MintMsg, TokenIdStrategy,
+    VerificationStatus, VerifierConfig,
+};
+use crate::payment::validate_mint_fee;
+use crate::quorum::validate_quorum;
+use crate::staking::validate_staking;
+use crate::state::{AigcData, Cw7007Contract, Escrow, Extension, Proposal, Request, RequestStatus};
+use crate::template::validate_template;
 use crate::traits::Cw7007Execute;
+use crate::verifier::{parse_verifying_key, VerificationInput};
 use cw721_base::{
     ContractError, ExecuteMsg as Cw721ExecuteMsg, InstantiateMsg as Cw721InstantiateMsg,
 };
@@ -40,10 +54,69 @@ where
             },
         )?;
 
-        let prompt_info_data = PromptInfoResponse { prompt: msg.prompt };
-        let request_ids_data = RequestIdsResponse { ids: Vec::new() };
-        self.prompt_info.save(deps.storage, &prompt_info_data)?;
-        self.request_ids.save(deps.storage, &request_ids_data)?;
+        self.publish_prompt(deps.storage, &_env.block, msg.prompt)?;
+
+        if let Some(VerifierConfig::Contract { address }) = &msg.verifier {
+            deps.api.addr_validate(address)?;
+        }
+        self.verifier.save(deps.storage, &msg.verifier)?;
+
+        if let Some(config) = &msg.optimistic {
+            if config.challenge_window == 0 || config.challenge_bond.amount.is_zero() {
+                return Err(StdError::generic_err(
+                    "challenge window and bond must not be zero.",
+                ));
+            }
+            if let Some(arbiter) = &config.arbiter {
+                deps.api.addr_validate(arbiter)?;
+            }
+        }
+        self.optimistic.save(deps.storage, &msg.optimistic)?;
+        self.request_timeout
+            .save(deps.storage, &msg.request_timeout)?;
+
+        if let Some(config) = &msg.mint_fee {
+            validate_mint_fee(deps.api, config)?;
+        }
+        self.mint_fee.save(deps.storage, &msg.mint_fee)?;
+
+        if let Some(config) = &msg.staking {
+            validate_staking(config)?;
+        }
+        self.staking.save(deps.storage, &msg.staking)?;
+
+        if let Some(config) = &msg.assignment {
+            validate_assignment(config, msg.staking.is_some())?;
+        }
+        self.assignment.save(deps.storage, &msg.assignment)?;
+
+        if let Some(config) = &msg.quorum {
+            validate_quorum(
+                config,
+                msg.verifier.as_ref(),
+                msg.optimistic.is_some() || msg.assignment.is_some(),
+            )?;
+        }
+        self.quorum.save(deps.storage, &msg.quorum)?;
+        self.reveal_window.save(deps.storage, &msg.reveal_window)?;
+        let token_id_strategy = msg.token_id_strategy.unwrap_or(TokenIdStrategy::Counter {});
+        self.token_id_strategy
+            .save(deps.storage, &token_id_strategy)?;
+
+        if let Some(template) = &msg.prompt_template {
+            validate_template(template)?;
+        }
+        self.prompt_template
+            .save(deps.storage, &msg.prompt_template)?;
+
+        let callback_gas_limit = msg.callback_gas_limit.unwrap_or(DEFAULT_CALLBACK_GAS_LIMIT);
+        if callback_gas_limit == 0 {
+            return Err(StdError::generic_err(
+                "callback gas limit must not be zero.",
+            ));
+        }
+        self.callback_gas_limit
+            .save(deps.storage, &callback_gas_limit)?;
 
         Ok(Response::default())
     }
@@ -64,14 +137,120 @@ where
     ) -> Result<Response<C>, ContractError> {
         match msg {
             ExecuteMsg::Mint {
-                token_id: _,
+                token_id,
                 owner,
                 token_uri,
                 extension,
-            } => self.mint_anyone(deps, info, owner, token_uri, extension),
-            ExecuteMsg::Response { token_id, output } => {
-                self.response(deps, env, info, token_id, output)
+                prompt,
+                variables,
+                callback,
+                prompt_id,
+            } => self.mint_anyone(
+                deps,
+                env,
+                info,
+                MintMsg {
+                    token_id: Some(token_id),
+                    owner,
+                    token_uri,
+                    extension,
+                    prompt,
+                    variables,
+                    callback,
+                    prompt_id,
+                },
+            ),
+            ExecuteMsg::Response {
+                token_id,
+                output,
+                attestation,
+                generation,
+            } => self.response(deps, env, info, token_id, output, attestation, generation),
+            ExecuteMsg::Verify { token_id, proof } => self.verify(deps, env, info, token_id, proof),
+            ExecuteMsg::RegisterOracle { oracle, metadata } => {
+                self.register_oracle(deps, info, oracle, metadata)
+            }
+            ExecuteMsg::RemoveOracle { oracle } => self.remove_oracle(deps, info, oracle),
+            ExecuteMsg::PauseOracle { oracle } => self.set_oracle_active(deps, info, oracle, false),
+            ExecuteMsg::ResumeOracle { oracle } => self.set_oracle_active(deps, info, oracle, true),
+            ExecuteMsg::SetVerifyingKey { vk } => self.set_verifying_key(deps, info, vk),
+            ExecuteMsg::Bond { metadata } => self.bond(deps, info, metadata),
+            ExecuteMsg::Unbond { amount } => self.unbond(deps, env, info, amount),
+            ExecuteMsg::Claim {} => self.claim(deps, env, info),
+            ExecuteMsg::Slash { oracle, amount } => self.slash(deps, info, oracle, amount),
+            ExecuteMsg::CommitResponse { token_id, hash } => {
+                self.commit_response(deps, env, info, token_id, hash)
+            }
+            ExecuteMsg::RevealResponse {
+                token_id,
+                output,
+                salt,
+                attestation,
+                generation,
+            } => self.reveal_response(
+                deps,
+                env,
+                info,
+                token_id,
+                output,
+                salt,
+                attestation,
+                generation,
+            ),
+            ExecuteMsg::SubmitOutputHash {
+                token_id,
+                output_hash,
+            } => self.submit_output_hash(deps, env, info, token_id, output_hash),
+            ExecuteMsg::RevealOutput {
+                token_id,
+                output,
+                generation,
+            } => self.reveal_output(deps, env, info, token_id, output, generation),
+            ExecuteMsg::ReassignRequest { token_id } => {
+                self.reassign_request(deps, env, info, token_id)
+            }
+            ExecuteMsg::FailRequest { token_id, reason } => {
+                self.fail_request(deps, env, info, token_id, reason)
+            }
+            ExecuteMsg::CancelRequest { token_id } => {
+                self.cancel_request(deps, env, info, token_id)
             }
+            ExecuteMsg::ExpireRequest { token_id } => {
+                self.expire_request(deps, env, info, token_id)
+            }
+            ExecuteMsg::AllowModel {
+                model_id,
+                weights_hash,
+            } => self.allow_model(deps, info, model_id, weights_hash),
+            ExecuteMsg::DisallowModel { model_id } => self.disallow_model(deps, info, model_id),
+            ExecuteMsg::UpdatePrompt { prompt } => self.update_prompt(deps, env, info, prompt),
+            ExecuteMsg::AddPrompt {
+                prompt_id,
+                name,
+                prompt,
+                price,
+            } => self.add_prompt(deps, env, info, prompt_id, name, prompt, price),
+            ExecuteMsg::ClosePrompt { prompt_id } => self.close_prompt(deps, info, prompt_id),
+            ExecuteMsg::SetPromptTemplate { template } => {
+                self.set_prompt_template(deps, info, template)
+            }
+            ExecuteMsg::UpdateMintFee { mint_fee } => self.update_mint_fee(deps, info, mint_fee),
+            ExecuteMsg::Receive(wrapper) => self.receive_cw20(deps, env, info, wrapper),
+            ExecuteMsg::UpdateCw20Price { token, price } => {
+                self.update_cw20_price(deps, info, token, price)
+            }
+            ExecuteMsg::ChallengeResponse { token_id } => {
+                self.challenge_response(deps, env, info, token_id)
+            }
+            ExecuteMsg::ResolveChallenge { token_id, upheld } => {
+                self.resolve_challenge(deps, env, info, token_id, upheld)
+            }
+            ExecuteMsg::FinalizeResponse { token_id } => {
+                self.finalize_proposal(deps, env, info, token_id)
+            }
+            ExecuteMsg::AddHook { addr } => self.add_hook(deps, info, addr),
+            ExecuteMsg::RemoveHook { addr } => self.remove_hook(deps, info, addr),
+            ExecuteMsg::Burn { token_id } => self.burn(deps, env, info, token_id),
             _ => self.cw721.execute(deps, env, info, msg.into()),
         }
     }
@@ -92,43 +271,32 @@ where
         info: cosmwasm_std::MessageInfo,
         token_id: String,
         output: String,
+        attestation: Option<Attestation>,
+        generation: Option<Generation>,
     ) -> Result<Response<C>, Self::Err> {
-        cw_ownable::assert_owner(deps.storage, &info.sender)?;
-
-        let mut token: TokenInfo<Extension> = self.cw721.tokens.load(deps.storage, &token_id)?;
-        if let Some(mut extension) = token.extension {
-            if extension.image.is_some() {
-                return Err(ContractError::Std(cosmwasm_std::StdError::generic_err(
-                    "image field is already filled.",
-                )));
-            }
-            extension.image = Some(output.clone());
-            token.extension = Some(extension);
-        } else {
-            return Err(ContractError::Std(cosmwasm_std::StdError::generic_err(
-                "image field is required in extension.",
+        self.assert_oracle(deps.storage, &info.sender)?;
+        if self.quorum.load(deps.storage)?.is_some() {
+            return Err(ContractError::Std(StdError::generic_err(
+                "outputs go through SubmitOutputHash in quorum mode.",
             )));
         }
-        self.cw721.tokens.save(deps.storage, &token_id, &token)?;
 
-        // request update
-        let request_ids = self
-            .request_ids
-            .load(deps.storage)
-            .unwrap_or(RequestIdsResponse { ids: Vec::new() });
-        let new_ids: Vec<String> = request_ids
-            .ids
-            .into_iter()
-            .filter(|x| x != &token_id)
-            .collect();
-        self.request_ids
-            .save(deps.storage, &RequestIdsResponse { ids: new_ids })?;
+        // outputs committed by other oracles must not be copied from their reveal
+        if self.has_open_commitment(deps.storage, &env, &token_id, &info.sender)? {
+            return Err(ContractError::Std(StdError::generic_err(
+                "request has a pending commitment.",
+            )));
+        }
 
-        Ok(Response::new()
-            .add_attribute("action", "response")
-            .add_attribute("sender", info.sender)
-            .add_attribute("token_id", token_id)
-            .add_attribute("output", output))
+        self.accept_output(
+            deps,
+            &env,
+            &info.sender,
+            &token_id,
+            output,
+            attestation,
+            generation,
+        )
     }
 
     fn verify(
@@ -139,7 +307,257 @@ where
         token_id: String,
         proof: String,
     ) -> Result<Response<C>, Self::Err> {
-        todo!() // TODO
+        self.assert_oracle(deps.storage, &info.sender)?;
+
+        let verifier = self
+            .verifier
+            .load(deps.storage)?
+            .ok_or_else(|| StdError::generic_err("verification is not enabled."))?;
+
+        if self.verifications.has(deps.storage, &token_id) {
+            return Err(ContractError::Std(StdError::generic_err(
+                "token is already verified.",
+            )));
+        }
+
+        let mut record = self.aigc_data.load(deps.storage, &token_id)?;
+        let output = record
+            .aigc_data
+            .clone()
+            .ok_or_else(|| StdError::generic_err("token has no response yet."))?;
+        let input = VerificationInput {
+            prompt: self.token_prompt(deps.storage, &record)?,
+            input: record.prompt.clone(),
+            seed: self.requests.load(deps.storage, &token_id)?.seed,
+            output,
+        };
+
+        if !self.verify_proof(deps.as_ref(), &verifier, &input, &proof)? {
+            return Err(ContractError::Std(StdError::generic_err("invalid proof.")));
+        }
+
+        self.verifications.save(
+            deps.storage,
+            &token_id,
+            &VerificationStatus::Verified {
+                proof: proof.clone(),
+                verifier: info.sender.clone(),
+                block_height: env.block.height,
+                time: env.block.time,
+            },
+        )?;
+        record.proof = Some(proof.clone());
+        record.verified_at = Some(env.block.time);
+        self.aigc_data.save(deps.storage, &token_id, &record)?;
+
+        let hooks = self.hook_msgs(
+            deps.as_ref(),
+            Cw7007HookMsg::Verify {
+                token_id: token_id.clone(),
+                verifier: info.sender.to_string(),
+            },
+        )?;
+
+        Ok(Response::new()
+            .add_submessages(hooks)
+            .add_attribute("action", "verify")
+            .add_attribute("sender", info.sender)
+            .add_attribute("token_id", token_id)
+            .add_attribute("proof", proof))
+    }
+}
+
+impl<'a, C, E, Q> Cw7007Contract<'a, Extension, C, E, Q>
+where
+    C: CustomMsg,
+    E: CustomMsg,
+    Q: CustomMsg,
+{
+    /// Take `output` from `oracle`, as a proposal in optimistic mode or as the final output
+    #[allow(clippy::too_many_arguments)]
+    pub fn accept_output(
+        &self,
+        deps: DepsMut,
+        env: &Env,
+        oracle: &Addr,
+        token_id: &str,
+        output: String,
+        attestation: Option<Attestation>,
+        generation: Option<Generation>,
+    ) -> Result<Response<C>, ContractError> {
+        self.check_generation(deps.storage, token_id, generation.as_ref())?;
+        self.check_response(
+            deps.as_ref(),
+            env,
+            oracle,
+            token_id,
+            &output,
+            attestation.as_ref(),
+        )?;
+
+        // in optimistic mode the output only becomes final once the challenge window is over
+        if let Some(config) = self.optimistic.load(deps.storage)? {
+            if self.proposals.has(deps.storage, token_id) {
+                return Err(ContractError::Std(StdError::generic_err(
+                    "output is already proposed.",
+                )));
+            }
+            let proposal = Proposal {
+                output: output.clone(),
+                attestation,
+                generation,
+                proposer: oracle.clone(),
+                proposed_at: env.block.height,
+                challenge_deadline: env.block.height + config.challenge_window,
+                challenge: None,
+            };
+            self.proposals.save(deps.storage, token_id, &proposal)?;
+
+            return Ok(Response::new()
+                .add_attribute("action", "propose")
+                .add_attribute("sender", oracle)
+                .add_attribute("token_id", token_id)
+                .add_attribute("output", output)
+                .add_attribute(
+                    "challenge_deadline",
+                    proposal.challenge_deadline.to_string(),
+                ));
+        }
+
+        let res = self.finalize_response(
+            deps,
+            env,
+            oracle,
+            token_id,
+            &output,
+            attestation,
+            generation,
+        )?;
+
+        Ok(res
+            .add_attribute("action", "response")
+            .add_attribute("sender", oracle)
+            .add_attribute("token_id", token_id)
+            .add_attribute("output", output))
+    }
+
+    /// Make sure `output` can be accepted for the token from `oracle`.
+    /// The attestation is checked whenever given, and mandatory in oracle mode
+    pub fn check_response(
+        &self,
+        deps: Deps,
+        env: &Env,
+        oracle: &Addr,
+        token_id: &str,
+        output: &str,
+        attestation: Option<&Attestation>,
+    ) -> Result<(), ContractError> {
+        let request = self.load_open_request(deps.storage, env, token_id)?;
+        request.assert_assignee(oracle)?;
+
+        let record = self.aigc_data.load(deps.storage, token_id)?;
+
+        let oracle_mode = matches!(
+            self.verifier.load(deps.storage)?,
+            Some(VerifierConfig::Oracle {})
+        );
+        if let Some(attestation) = attestation {
+            // the oracle paid for the output has to be the one vouching for it
+            if attestation.oracle != oracle.as_str() {
+                return Err(ContractError::Std(StdError::generic_err(
+                    "attestation must be signed by the responding oracle.",
+                )));
+            }
+            let input = VerificationInput {
+                prompt: self.token_prompt(deps.storage, &record)?,
+                input: record.prompt,
+                seed: request.seed,
+                output: output.to_string(),
+            };
+            if !self.verify_attestation(deps, &input, attestation)? {
+                return Err(ContractError::Std(StdError::generic_err(
+                    "invalid attestation.",
+                )));
+            }
+        } else if oracle_mode {
+            return Err(ContractError::Std(StdError::generic_err(
+                "attestation is required.",
+            )));
+        }
+
+        Ok(())
+    }
+
+    /// Write `output` into the token and close its request, paying the oracle out of escrow
+    /// and notifying the callback contract of the request
+    #[allow(clippy::too_many_arguments)]
+    pub fn finalize_response(
+        &self,
+        deps: DepsMut,
+        env: &Env,
+        oracle: &Addr,
+        token_id: &str,
+        output: &str,
+        attestation: Option<Attestation>,
+        generation: Option<Generation>,
+    ) -> Result<Response<C>, ContractError> {
+        let mut record = self.aigc_data.load(deps.storage, token_id)?;
+        record.aigc_data = Some(output.to_string());
+        record.fulfilled_at = Some(env.block.time);
+
+        // mirrored into the metadata so that wallets and marketplaces display them
+        let mut token: TokenInfo<Extension> = self.cw721.tokens.load(deps.storage, token_id)?;
+        if let Some(extension) = token.extension.as_mut() {
+            extension.image = Some(output.to_string());
+            if let Some(generation) = &generation {
+                extension
+                    .attributes
+                    .get_or_insert_with(Vec::new)
+                    .extend(generation_traits(generation));
+            }
+        }
+        record.generation = generation;
+        self.cw721.tokens.save(deps.storage, token_id, &token)?;
+
+        // an oracle attestation doubles as the proof in oracle mode
+        if let (Some(VerifierConfig::Oracle {}), Some(attestation)) =
+            (self.verifier.load(deps.storage)?, attestation)
+        {
+            let proof = to_json_string(&attestation)?;
+            self.verifications.save(
+                deps.storage,
+                token_id,
+                &VerificationStatus::Verified {
+                    proof: proof.clone(),
+                    verifier: oracle.clone(),
+                    block_height: env.block.height,
+                    time: env.block.time,
+                },
+            )?;
+            record.proof = Some(proof);
+            record.verified_at = Some(env.block.time);
+        }
+        self.aigc_data.save(deps.storage, token_id, &record)?;
+
+        // request update
+        let mut request = self.requests.load(deps.storage, token_id)?;
+        request.transition(RequestStatus::Fulfilled, &env.block)?;
+        self.requests.save(deps.storage, token_id, &request)?;
+
+        let bounty = self.release_escrow(&request, oracle)?;
+        let hooks = self.hook_msgs(
+            deps.as_ref(),
+            Cw7007HookMsg::Response {
+                token_id: token_id.to_string(),
+                oracle: oracle.to_string(),
+                output: output.to_string(),
+            },
+        )?;
+        let mut res = Response::new().add_messages(bounty).add_submessages(hooks);
+        if let Some(callback) = self.callback_msg(deps.storage, &request, output)? {
+            res = res.add_submessage(callback);
+        }
+        Ok(res)
     }
 }
 
@@ -153,22 +571,40 @@ where
     pub fn mint_anyone(
         &self,
         deps: DepsMut,
+        env: Env,
         info: MessageInfo,
-        // token_id: String,
-        owner: String,
-        token_uri: Option<String>,
-        extension: T,
+        msg: MintMsg<T>,
     ) -> Result<Response<C>, ContractError> {
         // cw_ownable::assert_owner(deps.storage, &info.sender)?;
+        let escrow = self.collect_mint_fee(deps.storage, &info, msg.prompt_id.as_deref())?;
+        self.mint_request(deps, env, info.sender, escrow, msg)
+    }
+
+    /// Mint the token of a new request, whatever way it was paid for
+    pub fn mint_request(
+        &self,
+        deps: DepsMut,
+        env: Env,
+        requester: Addr,
+        escrow: Escrow,
+        msg: MintMsg<T>,
+    ) -> Result<Response<C>, ContractError> {
+        if let Some(callback) = &msg.callback {
+            deps.api.addr_validate(&callback.contract)?;
+        }
+        if let Some(prompt_id) = &msg.prompt_id {
+            self.load_open_prompt(deps.storage, prompt_id)?;
+        }
+        let prompt = self.mint_prompt(deps.storage, msg.prompt, msg.variables)?;
 
         // create the token
         let token = TokenInfo {
-            owner: deps.api.addr_validate(&owner)?,
+            owner: deps.api.addr_validate(&msg.owner)?,
             approvals: vec![],
-            token_uri,
-            extension,
+            token_uri: msg.token_uri,
+            extension: msg.extension,
         };
-        let token_id = self.cw721.token_count(deps.storage)?.to_string(); // counter
+        let token_id = self.new_token_id(deps.storage, msg.token_id, &prompt)?;
 
         self.cw721
             .tokens
@@ -178,27 +614,263 @@ where
             })?;
 
         self.cw721.increment_tokens(deps.storage)?;
+        let prompt_version = self.prompt_info.load(deps.storage)?.version;
+        self.aigc_data.save(
+            deps.storage,
+            &token_id,
+            &AigcData {
+                prompt_id: msg.prompt_id.clone(),
+                ..AigcData::new(prompt, prompt_version, &env.block)
+            },
+        )?;
+        if let Some(prompt_id) = &msg.prompt_id {
+            self.prompt_tokens
+                .save(deps.storage, (prompt_id, &token_id), &Empty {})?;
+        }
 
         // request update
-        let request_ids = self.request_ids.load(deps.storage).unwrap();
-        // .unwrap_or(RequestIdsResponse { ids: Vec::new() });
-        self.request_ids.save(
-            deps.storage,
-            &RequestIdsResponse {
-                ids: {
-                    let mut ids = request_ids.ids;
-                    ids.push(token_id.clone());
-                    ids
-                },
+        let expires = self
+            .request_timeout
+            .load(deps.storage)?
+            .map(|timeout| timeout.after(&env.block));
+        let mut request = Request {
+            expires,
+            escrow,
+            treasury_cut: self.treasury_cut(deps.storage)?,
+            callback: msg.callback,
+            ..Request::new(token_id.clone(), requester.clone(), &env)
+        };
+        self.assign(deps.storage, &env, &mut request, None)?;
+        self.requests.save(deps.storage, &token_id, &request)?;
+
+        let hooks = self.hook_msgs(
+            deps.as_ref(),
+            Cw7007HookMsg::Mint {
+                token_id: token_id.clone(),
+                requester: requester.to_string(),
+                owner: msg.owner.clone(),
+            },
+        )?;
+        let mut res = Response::new()
+            .add_submessages(hooks)
+            .add_attribute("action", "mint")
+            .add_attribute("minter", requester)
+            .add_attribute("owner", msg.owner)
+            .add_attribute("token_id", token_id)
+            .add_attribute("seed", request.seed.to_string());
+        if let Some(prompt_id) = msg.prompt_id {
+            res = res.add_attribute("prompt_id", prompt_id);
+        }
+        if let Some(assignee) = request.assignee {
+            res = res.add_attribute("assignee", assignee);
+        }
+        Ok(res)
+    }
+}
+
+impl<'a, T, C, E, Q> Cw7007Contract<'a, T, C, E, Q>
+where
+    T: Serialize + DeserializeOwned + Clone,
+    C: CustomMsg,
+    E: CustomMsg,
+    Q: CustomMsg,
+{
+    pub fn set_verifying_key(
+        &self,
+        deps: DepsMut,
+        info: MessageInfo,
+        vk: Binary,
+    ) -> Result<Response<C>, ContractError> {
+        cw_ownable::assert_owner(deps.storage, &info.sender)?;
+
+        parse_verifying_key(&vk)?;
+        self.verifying_key.save(deps.storage, &vk)?;
+
+        Ok(Response::new()
+            .add_attribute("action", "set_verifying_key")
+            .add_attribute("sender", info.sender))
+    }
+}
+
+impl<'a, T, C, E, Q> Cw7007Contract<'a, T, C, E, Q>
+where
+    T: Serialize + DeserializeOwned + Clone,
+    C: CustomMsg,
+    E: CustomMsg,
+    Q: CustomMsg,
+{
+    pub fn fail_request(
+        &self,
+        deps: DepsMut,
+        env: Env,
+        info: MessageInfo,
+        token_id: String,
+        reason: String,
+    ) -> Result<Response<C>, ContractError> {
+        self.assert_oracle(deps.storage, &info.sender)?;
+        if self.quorum.load(deps.storage)?.is_some() {
+            return Err(ContractError::Std(StdError::generic_err(
+                "a single oracle can not fail a request in quorum mode.",
+            )));
+        }
+        self.requests
+            .load(deps.storage, &token_id)?
+            .assert_assignee(&info.sender)?;
+
+        let request = self.close_request(
+            deps,
+            &env,
+            &token_id,
+            RequestStatus::Failed {
+                reason: reason.clone(),
             },
         )?;
 
         Ok(Response::new()
-            .add_attribute("action", "mint")
-            .add_attribute("minter", info.sender)
-            .add_attribute("owner", owner)
+            .add_messages(request.refund_msg()?)
+            .add_attribute("action", "fail_request")
+            .add_attribute("sender", info.sender)
+            .add_attribute("token_id", token_id)
+            .add_attribute("reason", reason))
+    }
+
+    pub fn cancel_request(
+        &self,
+        deps: DepsMut,
+        env: Env,
+        info: MessageInfo,
+        token_id: String,
+    ) -> Result<Response<C>, ContractError> {
+        let request = self.requests.load(deps.storage, &token_id)?;
+        if request.requester != info.sender {
+            return Err(ContractError::Ownership(
+                cw_ownable::OwnershipError::NotOwner,
+            ));
+        }
+        // while the oracle still has time, a refund would let the requester front-run
+        // a response seen in the mempool and keep the output for free
+        let overdue = request.is_expired(&env.block)
+            || request
+                .reassign_at
+                .is_some_and(|reassign_at| reassign_at.is_expired(&env.block));
+        if !overdue {
+            return Err(ContractError::Std(StdError::generic_err(
+                "request can only be cancelled once its oracle timed out.",
+            )));
+        }
+
+        let request = self.close_request(deps, &env, &token_id, RequestStatus::Cancelled)?;
+
+        Ok(Response::new()
+            .add_messages(request.refund_msg()?)
+            .add_attribute("action", "cancel_request")
+            .add_attribute("sender", info.sender)
             .add_attribute("token_id", token_id))
     }
+
+    pub fn expire_request(
+        &self,
+        deps: DepsMut,
+        env: Env,
+        info: MessageInfo,
+        token_id: String,
+    ) -> Result<Response<C>, ContractError> {
+        let request = self.requests.load(deps.storage, &token_id)?;
+        if !request.is_expired(&env.block) {
+            return Err(ContractError::Std(StdError::generic_err(
+                "request has not expired yet.",
+            )));
+        }
+
+        let request = self.close_request(deps, &env, &token_id, RequestStatus::Expired)?;
+
+        Ok(Response::new()
+            .add_messages(request.refund_msg()?)
+            .add_attribute("action", "expire_request")
+            .add_attribute("sender", info.sender)
+            .add_attribute("token_id", token_id))
+    }
+
+    /// Burn a token, cancelling its request if still pending so that the escrow
+    /// goes back to the requester instead of staying locked without a token to fulfill
+    pub fn burn(
+        &self,
+        mut deps: DepsMut,
+        env: Env,
+        info: MessageInfo,
+        token_id: String,
+    ) -> Result<Response<C>, ContractError> {
+        let hooks = self.hook_msgs(
+            deps.as_ref(),
+            Cw7007HookMsg::Burn {
+                token_id: token_id.clone(),
+                sender: info.sender.to_string(),
+            },
+        )?;
+        let res = self.cw721.execute(
+            deps.branch(),
+            env.clone(),
+            info,
+            Cw721ExecuteMsg::Burn {
+                token_id: token_id.clone(),
+            },
+        )?;
+
+        let pending = self
+            .requests
+            .may_load(deps.storage, &token_id)?
+            .is_some_and(|request| request.status == RequestStatus::Pending);
+        let refund = if pending {
+            self.close_request(deps, &env, &token_id, RequestStatus::Cancelled)?
+                .refund_msg()?
+        } else {
+            None
+        };
+
+        Ok(res.add_messages(refund).add_submessages(hooks))
+    }
+
+    /// Load a request that can still receive an output
+    pub fn load_open_request(
+        &self,
+        storage: &dyn Storage,
+        env: &Env,
+        token_id: &str,
+    ) -> Result<Request, ContractError> {
+        let request = self.requests.load(storage, token_id)?;
+        if request.status != RequestStatus::Pending {
+            return Err(ContractError::Std(StdError::generic_err(
+                "request is not pending.",
+            )));
+        }
+        if request.is_expired(&env.block) {
+            return Err(ContractError::Std(StdError::generic_err(
+                "request has expired.",
+            )));
+        }
+        Ok(request)
+    }
+
+    /// Close a pending request without output
+    fn close_request(
+        &self,
+        deps: DepsMut,
+        env: &Env,
+        token_id: &str,
+        status: RequestStatus,
+    ) -> Result<Request, ContractError> {
+        // a proposed output has to go through its challenge first
+        if self.proposals.has(deps.storage, token_id) {
+            return Err(ContractError::Std(StdError::generic_err(
+                "output is already proposed.",
+            )));
+        }
+
+        let mut request = self.requests.load(deps.storage, token_id)?;
+        request.transition(status, &env.block)?;
+        self.requests.save(deps.storage, token_id, &request)?;
+        Ok(request)
+    }
 }
 
 impl<T, E> From<ExecuteMsg<T, E>> for Cw721ExecuteMsg<T, E> {
@@ -209,6 +881,10 @@ impl<T, E> From<ExecuteMsg<T, E>> for Cw721ExecuteMsg<T, E> {
                 owner,
                 token_uri,
                 extension,
+                prompt: _,
+                variables: _,
+                callback: _,
+                prompt_id: _,
             } => Cw721ExecuteMsg::Mint {
                 token_id,
                 owner,
@@ -0,0 +1,63 @@
+use cosmwasm_std::{CustomMsg, Deps, DepsMut, MessageInfo, Response, StdError, StdResult, SubMsg};
+use cw721_base::ContractError;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+use crate::msg::Cw7007HookMsg;
+use crate::state::Cw7007Contract;
+
+impl<'a, T, C, E, Q> Cw7007Contract<'a, T, C, E, Q>
+where
+    T: Serialize + DeserializeOwned + Clone,
+    C: CustomMsg,
+    E: CustomMsg,
+    Q: CustomMsg,
+{
+    pub fn add_hook(
+        &self,
+        deps: DepsMut,
+        info: MessageInfo,
+        addr: String,
+    ) -> Result<Response<C>, ContractError> {
+        cw_ownable::assert_owner(deps.storage, &info.sender)?;
+
+        let hook = deps.api.addr_validate(&addr)?;
+        self.hooks
+            .add_hook(deps.storage, hook)
+            .map_err(|err| StdError::generic_err(err.to_string()))?;
+
+        Ok(Response::new()
+            .add_attribute("action", "add_hook")
+            .add_attribute("sender", info.sender)
+            .add_attribute("hook", addr))
+    }
+
+    pub fn remove_hook(
+        &self,
+        deps: DepsMut,
+        info: MessageInfo,
+        addr: String,
+    ) -> Result<Response<C>, ContractError> {
+        cw_ownable::assert_owner(deps.storage, &info.sender)?;
+
+        let hook = deps.api.addr_validate(&addr)?;
+        self.hooks
+            .remove_hook(deps.storage, hook)
+            .map_err(|err| StdError::generic_err(err.to_string()))?;
+
+        Ok(Response::new()
+            .add_attribute("action", "remove_hook")
+            .add_attribute("sender", info.sender)
+            .add_attribute("hook", addr))
+    }
+
+    /// One submessage per registered hook, carrying `msg`
+    pub fn hook_msgs(&self, deps: Deps, msg: Cw7007HookMsg) -> StdResult<Vec<SubMsg<C>>> {
+        self.hooks
+            .query_hooks(deps)?
+            .hooks
+            .into_iter()
+            .map(|hook| Ok(SubMsg::new(msg.clone().into_cosmos_msg(hook)?)))
+            .collect()
+    }
+}
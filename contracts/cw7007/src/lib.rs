@@ -1,14 +1,29 @@
+mod assignment;
+mod callback;
+mod commit;
 mod execute;
+mod hooks;
+mod migrate;
+mod model;
 pub mod msg;
+#[cfg(test)]
+mod multitest;
+mod optimistic;
+mod oracle;
+mod payment;
+mod prompt;
 mod query;
+mod quorum;
+mod staking;
 mod state;
-mod traits;
+mod template;
+mod token_id;
+pub mod traits;
+mod verifier;
 
-use cosmwasm_std::{
-    entry_point, Binary, Deps, DepsMut, Empty, Env, MessageInfo, Response, StdResult,
-};
+use cosmwasm_std::{Binary, Deps, DepsMut, Empty, Env, MessageInfo, Reply, Response, StdResult};
 use cw721_base::ContractError;
-use msg::{ExecuteMsg, InstantiateMsg, QueryMsg};
+use msg::{ExecuteMsg, InstantiateMsg, MigrateMsg, QueryMsg};
 use state::Cw7007Contract;
 use state::Extension;
 
@@ -16,12 +31,11 @@ use state::Extension;
 const CONTRACT_NAME: &str = "crates.io:cw7007";
 const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
 
-#[cfg(not(feature = "library"))]
 pub mod entry {
     use super::*;
 
     // This makes a conscious choice on the various generics used by the contract
-    #[entry_point]
+    #[cfg_attr(not(feature = "library"), cosmwasm_std::entry_point)]
     pub fn instantiate(
         deps: DepsMut,
         env: Env,
@@ -34,40 +48,34 @@ pub mod entry {
         contract.instantiate(deps, env, info, msg)
     }
 
-    #[entry_point]
+    #[cfg_attr(not(feature = "library"), cosmwasm_std::entry_point)]
     pub fn execute(
         deps: DepsMut,
         env: Env,
         info: MessageInfo,
         msg: ExecuteMsg<Extension, Empty>,
     ) -> Result<Response, ContractError> {
-        let should_check_extension = matches!(msg, ExecuteMsg::Mint { .. });
-        let extension = if let ExecuteMsg::Mint { extension, .. } = &msg {
-            extension.clone()
-        } else {
-            None
-        };
-        if should_check_extension {
-            if let Some(ext) = &extension {
-                println!("Checking extension: {:?}", ext);
-
-                if ext.description.is_none() {
-                    return Err(ContractError::Std(cosmwasm_std::StdError::generic_err(
-                        "Description is required in extension.",
-                    )));
-                }
-            }
-        }
-
         let contract = Cw7007Contract::<Extension, Empty, Empty, Empty>::default();
         contract.execute(deps, env, info, msg)
     }
 
-    #[entry_point]
+    #[cfg_attr(not(feature = "library"), cosmwasm_std::entry_point)]
     pub fn query(deps: Deps, env: Env, msg: QueryMsg<Empty>) -> StdResult<Binary> {
         let contract = Cw7007Contract::<Extension, Empty, Empty, Empty>::default();
         contract.query(deps, env, msg)
     }
+
+    #[cfg_attr(not(feature = "library"), cosmwasm_std::entry_point)]
+    pub fn reply(deps: DepsMut, env: Env, msg: Reply) -> Result<Response, ContractError> {
+        let contract = Cw7007Contract::<Extension, Empty, Empty, Empty>::default();
+        contract.reply(deps, env, msg)
+    }
+
+    #[cfg_attr(not(feature = "library"), cosmwasm_std::entry_point)]
+    pub fn migrate(deps: DepsMut, env: Env, msg: MigrateMsg) -> Result<Response, ContractError> {
+        let contract = Cw7007Contract::<Extension, Empty, Empty, Empty>::default();
+        contract.migrate(deps, env, msg)
+    }
 }
 
 #[cfg(test)]
@@ -75,15 +83,98 @@ mod tests {
     use super::*;
 
     use cosmwasm_std::{
-        from_binary,
-        testing::{mock_dependencies, mock_env, mock_info},
+        coins, from_json,
+        testing::{mock_dependencies, mock_env, mock_info, MockQuerier},
+        to_json_binary, ContractResult, SystemError, SystemResult, WasmQuery,
     };
     use cw721::NftInfoResponse;
-    use msg::{ExecuteMsg, InstantiateMsg, QueryMsg};
-    use state::Metadata;
+    use msg::{
+        AssignmentConfig, AssignmentStrategy, Attestation, Charset, ExecuteMsg, Generation,
+        InferenceParams, InstantiateMsg, MintFeeConfig, MintMsg, ModelsResponse, OptimisticConfig,
+        OracleKey, OracleMetadata, OraclesResponse, PromptInfoResponse, PromptTemplate,
+        PromptVariable, PromptVersionsResponse, QueryMsg, QuorumConfig, ReceiveMsg,
+        RegisteredPromptsResponse, RequestIdsResponse, SignatureScheme, StakingConfig,
+        TemplateVariable, TokenIdStrategy, VerificationResponse, VerificationStatus,
+        VerifierConfig, VerifierQueryMsg, VerifyProofResponse,
+    };
+    use state::{AigcData, Metadata, Oracle, Proposal, Reputation, Request, RequestStatus};
+    use verifier::VerificationInput;
 
     const CREATOR: &str = "creator";
 
+    /// Plain collection with every optional feature off, to be overridden per test
+    fn default_init() -> InstantiateMsg {
+        InstantiateMsg {
+            name: "Cats".to_string(),
+            symbol: "MEOW".to_string(),
+            minter: CREATOR.to_string(),
+            prompt: "You are a cat. Just answer with 'MEOW'.".to_string(),
+            verifier: None,
+            optimistic: None,
+            request_timeout: None,
+            mint_fee: None,
+            staking: None,
+            assignment: None,
+            quorum: None,
+            reveal_window: None,
+            token_id_strategy: None,
+            prompt_template: None,
+            callback_gas_limit: None,
+        }
+    }
+
+    const VERIFIER: &str = "verifier";
+
+    /// Stand-in for an external verifier contract, accepting the hex encoded
+    /// canonical hash of the verification input as proof
+    fn mock_verifier(querier: &mut MockQuerier) {
+        querier.update_wasm(|query| match query {
+            WasmQuery::Smart { contract_addr, msg } if contract_addr == VERIFIER => {
+                let VerifierQueryMsg::VerifyProof {
+                    prompt,
+                    input,
+                    seed,
+                    output,
+                    proof,
+                } = from_json(msg).unwrap();
+                let hash = VerificationInput {
+                    prompt,
+                    input,
+                    seed,
+                    output,
+                }
+                .hash();
+                let res = VerifyProofResponse {
+                    valid: cosmwasm_std::HexBinary::from(hash).to_hex() == proof,
+                };
+                SystemResult::Ok(ContractResult::Ok(to_json_binary(&res).unwrap()))
+            }
+            _ => SystemResult::Err(SystemError::UnsupportedRequest {
+                kind: "wasm".to_string(),
+            }),
+        });
+    }
+
+    fn register_oracle(
+        contract: &Cw7007Contract<Extension, Empty, Empty, Empty>,
+        deps: DepsMut,
+        oracle: &str,
+        key: Option<OracleKey>,
+    ) {
+        let exec_msg = ExecuteMsg::RegisterOracle {
+            oracle: oracle.to_string(),
+            metadata: OracleMetadata {
+                name: oracle.to_string(),
+                endpoint: "https://oracle.example.com".to_string(),
+                key,
+                models: vec!["llama3".to_string()],
+            },
+        };
+        contract
+            .execute(deps, mock_env(), mock_info(CREATOR, &[]), exec_msg)
+            .unwrap();
+    }
+
     /// Make sure cw2 version info is properly initialized during instantiation,
     /// and NOT overwritten by the base contract.
     #[test]
@@ -98,7 +189,7 @@ mod tests {
                 name: "".into(),
                 symbol: "".into(),
                 minter: "larry".into(),
-                prompt: "You are a cat. Just answer with 'MEOW'.".into(),
+                ..default_init()
             },
         )
         .unwrap();
@@ -117,24 +208,27 @@ mod tests {
         let init_msg = InstantiateMsg {
             name: "SpaceShips".to_string(),
             symbol: "SPACE".to_string(),
-            minter: CREATOR.to_string(),
-            prompt: "You are a cat. Just answer with 'MEOW'.".into(),
+            ..default_init()
         };
         contract
             .instantiate(deps.as_mut(), mock_env(), info.clone(), init_msg)
             .unwrap();
 
         let token_uri = Some("https://starships.example.com/Starship/Enterprise.json".into());
-        let extension = Some(Metadata {
+        let extension = Metadata {
             description: Some("Spaceship with Warp Drive".into()),
             name: Some("Starship USS Enterprise".to_string()),
             ..Metadata::default()
-        });
+        };
         let exec_msg = ExecuteMsg::Mint {
             token_id: "Not used".to_string(),
             owner: "john".to_string(),
             token_uri: token_uri.clone(),
-            extension: extension.clone(),
+            extension: Some(extension.clone()),
+            prompt: "Draw the USS Enterprise".to_string(),
+            variables: None,
+            callback: None,
+            prompt_id: None,
         };
         contract
             .execute(deps.as_mut(), mock_env(), info, exec_msg)
@@ -147,15 +241,2648 @@ mod tests {
         match res {
             Ok(binary_res) => {
                 let res: NftInfoResponse<Metadata> =
-                    from_binary(&binary_res).expect("Failed to parse binary response");
+                    from_json(&binary_res).expect("Failed to parse binary response");
                 assert_eq!(res.token_uri, token_uri, "Token URI does not match");
-                assert_eq!(
-                    res.extension,
-                    extension.expect("Extension is None"),
-                    "Extension does not match"
-                );
+                assert_eq!(res.extension, extension, "Extension does not match");
             }
             Err(err) => panic!("Query failed: {:?}", err),
         };
     }
+
+    #[test]
+    fn per_token_aigc_data() {
+        let mut deps = mock_dependencies();
+        let contract = Cw7007Contract::<Extension, Empty, Empty, Empty>::default();
+
+        let info = mock_info(CREATOR, &[]);
+        let init_msg = default_init();
+        contract
+            .instantiate(deps.as_mut(), mock_env(), info.clone(), init_msg)
+            .unwrap();
+        register_oracle(&contract, deps.as_mut(), CREATOR, None);
+
+        // the prompt is mandatory, metadata is not
+        let mint = |prompt: &str| ExecuteMsg::Mint {
+            token_id: "Not used".to_string(),
+            owner: "john".to_string(),
+            token_uri: None,
+            extension: None,
+            prompt: prompt.to_string(),
+            variables: None,
+            callback: None,
+            prompt_id: None,
+        };
+        contract
+            .execute(deps.as_mut(), mock_env(), info.clone(), mint(""))
+            .unwrap_err();
+        contract
+            .execute(deps.as_mut(), mock_env(), info.clone(), mint("Hello"))
+            .unwrap();
+
+        let query_msg: QueryMsg<Empty> = QueryMsg::AigcData {
+            token_id: "0".to_string(),
+        };
+        let res: AigcData = from_json(
+            contract
+                .query(deps.as_ref(), mock_env(), query_msg.clone())
+                .unwrap(),
+        )
+        .unwrap();
+        assert_eq!(
+            res,
+            AigcData::new("Hello".to_string(), 1, &mock_env().block)
+        );
+
+        let mut env = mock_env();
+        env.block.time = env.block.time.plus_seconds(5);
+        let exec_msg = ExecuteMsg::Response {
+            token_id: "0".to_string(),
+            output: "MEOW".to_string(),
+            attestation: None,
+            generation: None,
+        };
+        contract
+            .execute(deps.as_mut(), env.clone(), info, exec_msg)
+            .unwrap();
+
+        let res: AigcData = from_json(
+            contract
+                .query(deps.as_ref(), mock_env(), query_msg)
+                .unwrap(),
+        )
+        .unwrap();
+        assert_eq!(res.aigc_data, Some("MEOW".to_string()));
+        assert_eq!(res.fulfilled_at, Some(env.block.time));
+        assert_eq!(res.proof, None);
+    }
+
+    #[test]
+    fn token_id_strategies() {
+        use sha2::{Digest, Sha256};
+
+        let setup = |strategy: TokenIdStrategy| {
+            let mut deps = mock_dependencies();
+            let contract = Cw7007Contract::<Extension, Empty, Empty, Empty>::default();
+            let init_msg = InstantiateMsg {
+                token_id_strategy: Some(strategy),
+                ..default_init()
+            };
+            contract
+                .instantiate(deps.as_mut(), mock_env(), mock_info(CREATOR, &[]), init_msg)
+                .unwrap();
+            (deps, contract)
+        };
+        let mint = |token_id: &str, prompt: &str| ExecuteMsg::Mint {
+            token_id: token_id.to_string(),
+            owner: "john".to_string(),
+            token_uri: None,
+            extension: None,
+            prompt: prompt.to_string(),
+            variables: None,
+            callback: None,
+            prompt_id: None,
+        };
+        let minted_id = |res: Response| {
+            res.attributes
+                .into_iter()
+                .find(|attr| attr.key == "token_id")
+                .unwrap()
+                .value
+        };
+
+        // counter ids are not reused after a burn
+        let (mut deps, contract) = setup(TokenIdStrategy::Counter {});
+        for prompt in ["Hello", "World"] {
+            contract
+                .execute(
+                    deps.as_mut(),
+                    mock_env(),
+                    mock_info("john", &[]),
+                    mint("", prompt),
+                )
+                .unwrap();
+        }
+        let burn = ExecuteMsg::Burn {
+            token_id: "1".to_string(),
+        };
+        contract
+            .execute(deps.as_mut(), mock_env(), mock_info("john", &[]), burn)
+            .unwrap();
+        let res = contract
+            .execute(
+                deps.as_mut(),
+                mock_env(),
+                mock_info("john", &[]),
+                mint("", "Hello"),
+            )
+            .unwrap();
+        assert_eq!(minted_id(res), "2");
+
+        // caller ids are validated and unique
+        let (mut deps, contract) = setup(TokenIdStrategy::Caller {});
+        for token_id in ["", "white space", &"a".repeat(65)] {
+            contract
+                .execute(
+                    deps.as_mut(),
+                    mock_env(),
+                    mock_info("john", &[]),
+                    mint(token_id, "Hello"),
+                )
+                .unwrap_err();
+        }
+        let res = contract
+            .execute(
+                deps.as_mut(),
+                mock_env(),
+                mock_info("john", &[]),
+                mint("cat-1", "Hello"),
+            )
+            .unwrap();
+        assert_eq!(minted_id(res), "cat-1");
+        contract
+            .execute(
+                deps.as_mut(),
+                mock_env(),
+                mock_info("john", &[]),
+                mint("cat-1", "World"),
+            )
+            .unwrap_err();
+
+        // prompt hash ids can be computed off-chain, a prompt is minted only once
+        let (mut deps, contract) = setup(TokenIdStrategy::PromptHash {});
+        let res = contract
+            .execute(
+                deps.as_mut(),
+                mock_env(),
+                mock_info("john", &[]),
+                mint("ignored", "Hello"),
+            )
+            .unwrap();
+        assert_eq!(
+            minted_id(res),
+            cosmwasm_std::HexBinary::from(Sha256::digest(b"Hello").as_slice()).to_hex()
+        );
+        let err = contract
+            .execute(
+                deps.as_mut(),
+                mock_env(),
+                mock_info("john", &[]),
+                mint("ignored", "Hello"),
+            )
+            .unwrap_err();
+        assert_eq!(err.to_string(), "Generic error: prompt was already minted.");
+    }
+
+    #[test]
+    fn prompt_template_rendering() {
+        let mut deps = mock_dependencies();
+        let contract = Cw7007Contract::<Extension, Empty, Empty, Empty>::default();
+
+        let template = PromptTemplate {
+            template: "Draw a {color} {animal} in {style} style {{no placeholder}}".to_string(),
+            variables: vec![
+                TemplateVariable {
+                    name: "color".to_string(),
+                    max_length: 10,
+                    choices: None,
+                    charset: Some(Charset::Alphabetic {}),
+                },
+                TemplateVariable {
+                    name: "animal".to_string(),
+                    max_length: 10,
+                    choices: Some(vec!["cat".to_string(), "dog".to_string()]),
+                    charset: None,
+                },
+                TemplateVariable {
+                    name: "style".to_string(),
+                    max_length: 20,
+                    choices: None,
+                    charset: Some(Charset::Printable {}),
+                },
+            ],
+        };
+        let init_msg = |template: PromptTemplate| InstantiateMsg {
+            prompt: "You are an artist.".to_string(),
+            prompt_template: Some(template),
+            ..default_init()
+        };
+
+        // every placeholder has to be declared
+        let mut undeclared = template.clone();
+        undeclared.variables.pop();
+        contract
+            .instantiate(
+                deps.as_mut(),
+                mock_env(),
+                mock_info(CREATOR, &[]),
+                init_msg(undeclared),
+            )
+            .unwrap_err();
+        contract
+            .instantiate(
+                deps.as_mut(),
+                mock_env(),
+                mock_info(CREATOR, &[]),
+                init_msg(template),
+            )
+            .unwrap();
+
+        let mint = |prompt: &str, values: &[(&str, &str)]| ExecuteMsg::Mint {
+            token_id: "Not used".to_string(),
+            owner: "john".to_string(),
+            token_uri: None,
+            extension: None,
+            prompt: prompt.to_string(),
+            variables: Some(
+                values
+                    .iter()
+                    .map(|(name, value)| PromptVariable {
+                        name: name.to_string(),
+                        value: value.to_string(),
+                    })
+                    .collect(),
+            ),
+            callback: None,
+            prompt_id: None,
+        };
+        let valid = [("color", "red"), ("animal", "cat"), ("style", "pixel-art")];
+        for invalid in [
+            mint("Draw a cat", &valid),
+            mint("", &valid[..2]),
+            mint(
+                "",
+                &[("color", "red"), ("animal", "cow"), ("style", "pixel-art")],
+            ),
+            mint(
+                "",
+                &[("color", "r3d"), ("animal", "cat"), ("style", "pixel-art")],
+            ),
+            mint("", &[valid[0], valid[1], valid[2], ("size", "big")]),
+        ] {
+            contract
+                .execute(deps.as_mut(), mock_env(), mock_info("john", &[]), invalid)
+                .unwrap_err();
+        }
+        contract
+            .execute(
+                deps.as_mut(),
+                mock_env(),
+                mock_info("john", &[]),
+                mint("", &valid),
+            )
+            .unwrap();
+
+        let query_msg: QueryMsg<Empty> = QueryMsg::AigcData {
+            token_id: "0".to_string(),
+        };
+        let res: AigcData = from_json(
+            contract
+                .query(deps.as_ref(), mock_env(), query_msg)
+                .unwrap(),
+        )
+        .unwrap();
+        assert_eq!(
+            res.prompt,
+            "Draw a red cat in pixel-art style {no placeholder}"
+        );
+
+        // without a template minters write their prompt again
+        let exec_msg = ExecuteMsg::SetPromptTemplate { template: None };
+        contract
+            .execute(
+                deps.as_mut(),
+                mock_env(),
+                mock_info("john", &[]),
+                exec_msg.clone(),
+            )
+            .unwrap_err();
+        contract
+            .execute(deps.as_mut(), mock_env(), mock_info(CREATOR, &[]), exec_msg)
+            .unwrap();
+        contract
+            .execute(
+                deps.as_mut(),
+                mock_env(),
+                mock_info("john", &[]),
+                mint("", &valid),
+            )
+            .unwrap_err();
+        let exec_msg = ExecuteMsg::Mint {
+            token_id: "Not used".to_string(),
+            owner: "john".to_string(),
+            token_uri: None,
+            extension: None,
+            prompt: "Draw a cat".to_string(),
+            variables: None,
+            callback: None,
+            prompt_id: None,
+        };
+        contract
+            .execute(deps.as_mut(), mock_env(), mock_info("john", &[]), exec_msg)
+            .unwrap();
+    }
+
+    #[test]
+    fn prompt_versioning() {
+        let mut deps = mock_dependencies();
+        mock_verifier(&mut deps.querier);
+        let contract = Cw7007Contract::<Extension, Empty, Empty, Empty>::default();
+
+        let info = mock_info(CREATOR, &[]);
+        let init_msg = InstantiateMsg {
+            prompt: "You are a cat.".to_string(),
+            verifier: Some(VerifierConfig::Contract {
+                address: VERIFIER.to_string(),
+            }),
+            ..default_init()
+        };
+        contract
+            .instantiate(deps.as_mut(), mock_env(), info.clone(), init_msg)
+            .unwrap();
+        register_oracle(&contract, deps.as_mut(), CREATOR, None);
+
+        let mint = ExecuteMsg::Mint {
+            token_id: "Not used".to_string(),
+            owner: "john".to_string(),
+            token_uri: None,
+            extension: None,
+            prompt: "Hello".to_string(),
+            variables: None,
+            callback: None,
+            prompt_id: None,
+        };
+        contract
+            .execute(deps.as_mut(), mock_env(), info.clone(), mint.clone())
+            .unwrap();
+        let exec_msg = ExecuteMsg::Response {
+            token_id: "0".to_string(),
+            output: "MEOW".to_string(),
+            attestation: None,
+            generation: None,
+        };
+        contract
+            .execute(deps.as_mut(), mock_env(), info.clone(), exec_msg)
+            .unwrap();
+
+        let exec_msg = ExecuteMsg::UpdatePrompt {
+            prompt: "You are a dog.".to_string(),
+        };
+        contract
+            .execute(
+                deps.as_mut(),
+                mock_env(),
+                mock_info("john", &[]),
+                exec_msg.clone(),
+            )
+            .unwrap_err();
+        contract
+            .execute(deps.as_mut(), mock_env(), info.clone(), exec_msg)
+            .unwrap();
+        contract
+            .execute(deps.as_mut(), mock_env(), info.clone(), mint)
+            .unwrap();
+
+        let res: PromptInfoResponse = from_json(
+            contract
+                .query(deps.as_ref(), mock_env(), QueryMsg::Prompt {})
+                .unwrap(),
+        )
+        .unwrap();
+        assert_eq!(res.version, 2);
+        let res: PromptVersionsResponse = from_json(
+            contract
+                .query(
+                    deps.as_ref(),
+                    mock_env(),
+                    QueryMsg::PromptVersions {
+                        start_after: None,
+                        limit: None,
+                    },
+                )
+                .unwrap(),
+        )
+        .unwrap();
+        assert_eq!(
+            res.versions
+                .iter()
+                .map(|version| version.prompt.as_str())
+                .collect::<Vec<_>>(),
+            vec!["You are a cat.", "You are a dog."]
+        );
+
+        for (token_id, version) in [("0", 1), ("1", 2)] {
+            let query_msg: QueryMsg<Empty> = QueryMsg::AigcData {
+                token_id: token_id.to_string(),
+            };
+            let res: AigcData = from_json(
+                contract
+                    .query(deps.as_ref(), mock_env(), query_msg)
+                    .unwrap(),
+            )
+            .unwrap();
+            assert_eq!(res.prompt_version, version);
+        }
+
+        // the old token is still verified against the prompt it was generated under
+        let proof = cosmwasm_std::HexBinary::from(
+            VerificationInput {
+                prompt: "You are a cat.".into(),
+                input: "Hello".into(),
+                seed: model::generation_seed(&mock_env(), "0"),
+                output: "MEOW".into(),
+            }
+            .hash(),
+        )
+        .to_hex();
+        let verify_msg = ExecuteMsg::Verify {
+            token_id: "0".to_string(),
+            proof,
+        };
+        contract
+            .execute(deps.as_mut(), mock_env(), info, verify_msg)
+            .unwrap();
+    }
+
+    #[test]
+    fn prompt_registry() {
+        let mut deps = mock_dependencies();
+        mock_verifier(&mut deps.querier);
+        let contract = Cw7007Contract::<Extension, Empty, Empty, Empty>::default();
+
+        let info = mock_info(CREATOR, &[]);
+        let init_msg = InstantiateMsg {
+            name: "Pets".to_string(),
+            symbol: "PET".to_string(),
+            prompt: "You are a cat.".to_string(),
+            verifier: Some(VerifierConfig::Contract {
+                address: VERIFIER.to_string(),
+            }),
+            ..default_init()
+        };
+        contract
+            .instantiate(deps.as_mut(), mock_env(), info.clone(), init_msg)
+            .unwrap();
+        register_oracle(&contract, deps.as_mut(), CREATOR, None);
+
+        let add = |prompt_id: &str| ExecuteMsg::AddPrompt {
+            prompt_id: prompt_id.to_string(),
+            name: "Dogs".to_string(),
+            prompt: "You are a dog.".to_string(),
+            price: Some(coins(5, "ucat")),
+        };
+        contract
+            .execute(
+                deps.as_mut(),
+                mock_env(),
+                mock_info("john", &[]),
+                add("dogs"),
+            )
+            .unwrap_err();
+        contract
+            .execute(deps.as_mut(), mock_env(), info.clone(), add("big dogs"))
+            .unwrap_err();
+        contract
+            .execute(deps.as_mut(), mock_env(), info.clone(), add("dogs"))
+            .unwrap();
+        contract
+            .execute(deps.as_mut(), mock_env(), info.clone(), add("dogs"))
+            .unwrap_err();
+
+        let mint = |prompt_id: Option<&str>| ExecuteMsg::Mint {
+            token_id: "Not used".to_string(),
+            owner: "john".to_string(),
+            token_uri: None,
+            extension: None,
+            prompt: "Hello".to_string(),
+            variables: None,
+            callback: None,
+            prompt_id: prompt_id.map(str::to_string),
+        };
+        // registered prompts have their own price, the collection prompt stays free
+        contract
+            .execute(deps.as_mut(), mock_env(), info.clone(), mint(Some("dogs")))
+            .unwrap_err();
+        contract
+            .execute(deps.as_mut(), mock_env(), info.clone(), mint(Some("cows")))
+            .unwrap_err();
+        contract
+            .execute(
+                deps.as_mut(),
+                mock_env(),
+                mock_info(CREATOR, &coins(5, "ucat")),
+                mint(Some("dogs")),
+            )
+            .unwrap();
+        contract
+            .execute(deps.as_mut(), mock_env(), info.clone(), mint(None))
+            .unwrap();
+
+        let query_msg: QueryMsg<Empty> = QueryMsg::TokensByPrompt {
+            prompt_id: "dogs".to_string(),
+            start_after: None,
+            limit: None,
+        };
+        let res: cw721::TokensResponse = from_json(
+            contract
+                .query(deps.as_ref(), mock_env(), query_msg)
+                .unwrap(),
+        )
+        .unwrap();
+        assert_eq!(res.tokens, vec!["0".to_string()]);
+        let query_msg: QueryMsg<Empty> = QueryMsg::AigcData {
+            token_id: "0".to_string(),
+        };
+        let res: AigcData = from_json(
+            contract
+                .query(deps.as_ref(), mock_env(), query_msg)
+                .unwrap(),
+        )
+        .unwrap();
+        assert_eq!(res.prompt_id, Some("dogs".to_string()));
+
+        // the collection prompt is still the default one
+        let res: PromptInfoResponse = from_json(
+            contract
+                .query(deps.as_ref(), mock_env(), QueryMsg::Prompt {})
+                .unwrap(),
+        )
+        .unwrap();
+        assert_eq!(res.prompt, "You are a cat.");
+
+        // tokens are verified against the prompt they were minted under
+        for (token_id, prompt) in [("0", "You are a dog."), ("1", "You are a cat.")] {
+            let exec_msg = ExecuteMsg::Response {
+                token_id: token_id.to_string(),
+                output: "WOOF".to_string(),
+                attestation: None,
+                generation: None,
+            };
+            contract
+                .execute(deps.as_mut(), mock_env(), info.clone(), exec_msg)
+                .unwrap();
+            let proof = cosmwasm_std::HexBinary::from(
+                VerificationInput {
+                    prompt: prompt.into(),
+                    input: "Hello".into(),
+                    seed: model::generation_seed(&mock_env(), token_id),
+                    output: "WOOF".into(),
+                }
+                .hash(),
+            )
+            .to_hex();
+            let verify_msg = ExecuteMsg::Verify {
+                token_id: token_id.to_string(),
+                proof,
+            };
+            contract
+                .execute(deps.as_mut(), mock_env(), info.clone(), verify_msg)
+                .unwrap();
+        }
+
+        let exec_msg = ExecuteMsg::ClosePrompt {
+            prompt_id: "dogs".to_string(),
+        };
+        contract
+            .execute(deps.as_mut(), mock_env(), info.clone(), exec_msg)
+            .unwrap();
+        contract
+            .execute(
+                deps.as_mut(),
+                mock_env(),
+                mock_info(CREATOR, &coins(5, "ucat")),
+                mint(Some("dogs")),
+            )
+            .unwrap_err();
+
+        let query_msg: QueryMsg<Empty> = QueryMsg::RegisteredPrompts {
+            start_after: None,
+            limit: None,
+        };
+        let res: RegisteredPromptsResponse = from_json(
+            contract
+                .query(deps.as_ref(), mock_env(), query_msg)
+                .unwrap(),
+        )
+        .unwrap();
+        assert_eq!(res.prompts.len(), 1);
+        assert_eq!(res.prompts[0].name, "Dogs");
+        assert!(!res.prompts[0].open);
+    }
+
+    #[test]
+    fn model_descriptors() {
+        let mut deps = mock_dependencies();
+        let contract = Cw7007Contract::<Extension, Empty, Empty, Empty>::default();
+
+        let info = mock_info(CREATOR, &[]);
+        let init_msg = InstantiateMsg {
+            prompt: "You are a cat.".to_string(),
+            ..default_init()
+        };
+        contract
+            .instantiate(deps.as_mut(), mock_env(), info.clone(), init_msg)
+            .unwrap();
+        register_oracle(&contract, deps.as_mut(), CREATOR, None);
+
+        let weights_hash = cosmwasm_std::HexBinary::from(&[7u8; 32]);
+        let exec_msg = ExecuteMsg::AllowModel {
+            model_id: "llama3".to_string(),
+            weights_hash: weights_hash.clone(),
+        };
+        contract
+            .execute(
+                deps.as_mut(),
+                mock_env(),
+                mock_info("john", &[]),
+                exec_msg.clone(),
+            )
+            .unwrap_err();
+        contract
+            .execute(deps.as_mut(), mock_env(), info.clone(), exec_msg)
+            .unwrap();
+
+        let exec_msg = ExecuteMsg::Mint {
+            token_id: "Not used".to_string(),
+            owner: "john".to_string(),
+            token_uri: None,
+            extension: Some(Metadata::default()),
+            prompt: "Hello".to_string(),
+            variables: None,
+            callback: None,
+            prompt_id: None,
+        };
+        let res = contract
+            .execute(deps.as_mut(), mock_env(), info.clone(), exec_msg.clone())
+            .unwrap();
+        let seed = |deps: Deps, token_id: &str| {
+            let query_msg: QueryMsg<Empty> = QueryMsg::Request {
+                token_id: token_id.to_string(),
+            };
+            let res: Request =
+                from_json(contract.query(deps, mock_env(), query_msg).unwrap()).unwrap();
+            res.seed
+        };
+        let request_seed = seed(deps.as_ref(), "0");
+        assert!(res
+            .attributes
+            .iter()
+            .any(|a| a.key == "seed" && a.value == request_seed.to_string()));
+
+        // derived again for the same inputs, different for another token
+        let env = mock_env();
+        assert_eq!(request_seed, model::generation_seed(&env, "0"));
+        contract
+            .execute(deps.as_mut(), mock_env(), info.clone(), exec_msg)
+            .unwrap();
+        assert_ne!(request_seed, seed(deps.as_ref(), "1"));
+
+        let generation = Generation {
+            model_id: "llama3".to_string(),
+            weights_hash,
+            params: InferenceParams {
+                temperature: cosmwasm_std::Decimal::percent(70),
+                top_p: cosmwasm_std::Decimal::percent(90),
+                max_tokens: 256,
+                seed: Some(request_seed),
+            },
+            oracle_version: "1.2.0".to_string(),
+        };
+        let response = |generation: Option<Generation>| ExecuteMsg::Response {
+            token_id: "0".to_string(),
+            output: "MEOW".to_string(),
+            attestation: None,
+            generation,
+        };
+        let mut unknown_model = generation.clone();
+        unknown_model.model_id = "gpt2".to_string();
+        let mut wrong_weights = generation.clone();
+        wrong_weights.weights_hash = cosmwasm_std::HexBinary::from(&[8u8; 32]);
+        let mut bad_params = generation.clone();
+        bad_params.params.top_p = cosmwasm_std::Decimal::percent(150);
+        let mut other_seed = generation.clone();
+        other_seed.params.seed = Some(request_seed.wrapping_add(1));
+        let mut no_seed = generation.clone();
+        no_seed.params.seed = None;
+        for invalid in [
+            None,
+            Some(unknown_model),
+            Some(wrong_weights),
+            Some(bad_params),
+            Some(other_seed),
+            Some(no_seed),
+        ] {
+            contract
+                .execute(deps.as_mut(), mock_env(), info.clone(), response(invalid))
+                .unwrap_err();
+        }
+        contract
+            .execute(
+                deps.as_mut(),
+                mock_env(),
+                info.clone(),
+                response(Some(generation.clone())),
+            )
+            .unwrap();
+
+        let query_msg: QueryMsg<Empty> = QueryMsg::Generation {
+            token_id: "0".to_string(),
+        };
+        let res: Option<Generation> = from_json(
+            contract
+                .query(deps.as_ref(), mock_env(), query_msg)
+                .unwrap(),
+        )
+        .unwrap();
+        assert_eq!(res, Some(generation));
+
+        // mirrored as traits for marketplaces
+        let query_msg: QueryMsg<Empty> = QueryMsg::NftInfo {
+            token_id: "0".to_string(),
+        };
+        let res: NftInfoResponse<Metadata> = from_json(
+            contract
+                .query(deps.as_ref(), mock_env(), query_msg)
+                .unwrap(),
+        )
+        .unwrap();
+        let traits = res.extension.attributes.unwrap();
+        assert!(traits
+            .iter()
+            .any(|t| t.trait_type == "model" && t.value == "llama3"));
+        assert!(traits
+            .iter()
+            .any(|t| t.trait_type == "seed" && t.value == request_seed.to_string()));
+
+        let exec_msg = ExecuteMsg::DisallowModel {
+            model_id: "llama3".to_string(),
+        };
+        contract
+            .execute(deps.as_mut(), mock_env(), info, exec_msg)
+            .unwrap();
+        let query_msg: QueryMsg<Empty> = QueryMsg::Models {
+            start_after: None,
+            limit: None,
+        };
+        let res: ModelsResponse = from_json(
+            contract
+                .query(deps.as_ref(), mock_env(), query_msg)
+                .unwrap(),
+        )
+        .unwrap();
+        assert!(res.models.is_empty());
+    }
+
+    #[test]
+    fn verify_with_contract_backend() {
+        let mut deps = mock_dependencies();
+        mock_verifier(&mut deps.querier);
+        let contract = Cw7007Contract::<Extension, Empty, Empty, Empty>::default();
+
+        let info = mock_info(CREATOR, &[]);
+        let prompt = "You are a cat. Just answer with 'MEOW'.".to_string();
+        let init_msg = InstantiateMsg {
+            prompt: prompt.clone(),
+            verifier: Some(VerifierConfig::Contract {
+                address: VERIFIER.to_string(),
+            }),
+            ..default_init()
+        };
+        contract
+            .instantiate(deps.as_mut(), mock_env(), info.clone(), init_msg)
+            .unwrap();
+        register_oracle(&contract, deps.as_mut(), CREATOR, None);
+
+        let exec_msg = ExecuteMsg::Mint {
+            token_id: "Not used".to_string(),
+            owner: "john".to_string(),
+            token_uri: None,
+            extension: Some(Metadata {
+                description: Some("Hello".into()),
+                ..Metadata::default()
+            }),
+            prompt: "Hello".to_string(),
+            variables: None,
+            callback: None,
+            prompt_id: None,
+        };
+        contract
+            .execute(deps.as_mut(), mock_env(), info.clone(), exec_msg)
+            .unwrap();
+
+        // nothing to verify before the response lands
+        let verify_msg = ExecuteMsg::Verify {
+            token_id: "0".to_string(),
+            proof: "00".to_string(),
+        };
+        contract
+            .execute(deps.as_mut(), mock_env(), info.clone(), verify_msg)
+            .unwrap_err();
+
+        let exec_msg = ExecuteMsg::Response {
+            token_id: "0".to_string(),
+            output: "MEOW".to_string(),
+            attestation: None,
+            generation: None,
+        };
+        contract
+            .execute(deps.as_mut(), mock_env(), info.clone(), exec_msg)
+            .unwrap();
+
+        let verify_msg = ExecuteMsg::Verify {
+            token_id: "0".to_string(),
+            proof: "00".to_string(),
+        };
+        contract
+            .execute(deps.as_mut(), mock_env(), info.clone(), verify_msg)
+            .unwrap_err();
+
+        let query_msg: QueryMsg<Empty> = QueryMsg::Verification {
+            token_id: "0".to_string(),
+        };
+        let res: VerificationResponse = from_json(
+            contract
+                .query(deps.as_ref(), mock_env(), query_msg)
+                .unwrap(),
+        )
+        .unwrap();
+        assert_eq!(res.status, VerificationStatus::Unverified);
+
+        let proof = |seed: u64| {
+            cosmwasm_std::HexBinary::from(
+                VerificationInput {
+                    prompt: prompt.clone(),
+                    input: "Hello".into(),
+                    seed,
+                    output: "MEOW".into(),
+                }
+                .hash(),
+            )
+            .to_hex()
+        };
+        // the proof covers the seed the output was sampled with
+        let seed = model::generation_seed(&mock_env(), "0");
+        let verify_msg = ExecuteMsg::Verify {
+            token_id: "0".to_string(),
+            proof: proof(seed.wrapping_add(1)),
+        };
+        contract
+            .execute(deps.as_mut(), mock_env(), info.clone(), verify_msg)
+            .unwrap_err();
+
+        let proof = proof(seed);
+        let verify_msg = ExecuteMsg::Verify {
+            token_id: "0".to_string(),
+            proof: proof.clone(),
+        };
+        // only oracles verify
+        contract
+            .execute(
+                deps.as_mut(),
+                mock_env(),
+                mock_info("auditor", &[]),
+                verify_msg.clone(),
+            )
+            .unwrap_err();
+        register_oracle(&contract, deps.as_mut(), "auditor", None);
+        contract
+            .execute(
+                deps.as_mut(),
+                mock_env(),
+                mock_info("auditor", &[]),
+                verify_msg,
+            )
+            .unwrap();
+
+        let query_msg: QueryMsg<Empty> = QueryMsg::Verification {
+            token_id: "0".to_string(),
+        };
+        let res: VerificationResponse = from_json(
+            contract
+                .query(deps.as_ref(), mock_env(), query_msg)
+                .unwrap(),
+        )
+        .unwrap();
+        assert_eq!(
+            res.status,
+            VerificationStatus::Verified {
+                proof: proof.clone(),
+                verifier: cosmwasm_std::Addr::unchecked("auditor"),
+                block_height: mock_env().block.height,
+                time: mock_env().block.time,
+            }
+        );
+
+        // the proof is kept along the token's aigc data
+        let query_msg: QueryMsg<Empty> = QueryMsg::AigcData {
+            token_id: "0".to_string(),
+        };
+        let res: AigcData = from_json(
+            contract
+                .query(deps.as_ref(), mock_env(), query_msg)
+                .unwrap(),
+        )
+        .unwrap();
+        assert_eq!(res.proof, Some(proof));
+        assert_eq!(res.verified_at, Some(mock_env().block.time));
+    }
+
+    #[test]
+    fn response_requires_oracle_attestation() {
+        use k256::ecdsa::{signature::hazmat::PrehashSigner, Signature, SigningKey};
+
+        let mut deps = mock_dependencies();
+        let contract = Cw7007Contract::<Extension, Empty, Empty, Empty>::default();
+
+        let info = mock_info(CREATOR, &[]);
+        let prompt = "You are a cat. Just answer with 'MEOW'.".to_string();
+        let init_msg = InstantiateMsg {
+            prompt: prompt.clone(),
+            verifier: Some(VerifierConfig::Oracle {}),
+            ..default_init()
+        };
+        contract
+            .instantiate(deps.as_mut(), mock_env(), info.clone(), init_msg)
+            .unwrap();
+
+        let secp_key = SigningKey::from_slice(&[7u8; 32]).unwrap();
+        let ed_key = ed25519_zebra::SigningKey::from([9u8; 32]);
+        for (oracle, scheme, pubkey) in [
+            (
+                "secp",
+                SignatureScheme::Secp256k1,
+                secp_key.verifying_key().to_sec1_bytes().to_vec(),
+            ),
+            (
+                "edwards",
+                SignatureScheme::Ed25519,
+                ed25519_zebra::VerificationKey::from(&ed_key)
+                    .as_ref()
+                    .to_vec(),
+            ),
+        ] {
+            register_oracle(
+                &contract,
+                deps.as_mut(),
+                oracle,
+                Some(OracleKey {
+                    scheme,
+                    pubkey: pubkey.into(),
+                }),
+            );
+        }
+
+        for token_id in ["0", "1"] {
+            let exec_msg = ExecuteMsg::Mint {
+                token_id: "Not used".to_string(),
+                owner: "john".to_string(),
+                token_uri: None,
+                extension: Some(Metadata {
+                    description: Some(format!("Hello {}", token_id)),
+                    ..Metadata::default()
+                }),
+                prompt: format!("Hello {}", token_id),
+                variables: None,
+                callback: None,
+                prompt_id: None,
+            };
+            contract
+                .execute(deps.as_mut(), mock_env(), info.clone(), exec_msg)
+                .unwrap();
+        }
+
+        let hash = |token_id: &str| {
+            VerificationInput {
+                prompt: prompt.clone(),
+                input: format!("Hello {}", token_id),
+                seed: model::generation_seed(&mock_env(), token_id),
+                output: "MEOW".into(),
+            }
+            .hash()
+        };
+
+        // a response without attestation is refused
+        let exec_msg = ExecuteMsg::Response {
+            token_id: "0".to_string(),
+            output: "MEOW".to_string(),
+            attestation: None,
+            generation: None,
+        };
+        contract
+            .execute(deps.as_mut(), mock_env(), mock_info("secp", &[]), exec_msg)
+            .unwrap_err();
+
+        // a signature over another token is refused
+        let signature: Signature = secp_key.sign_prehash(&hash("1")).unwrap();
+        let exec_msg = ExecuteMsg::Response {
+            token_id: "0".to_string(),
+            output: "MEOW".to_string(),
+            attestation: Some(Attestation {
+                oracle: "secp".to_string(),
+                signature: signature.to_bytes().to_vec().into(),
+            }),
+            generation: None,
+        };
+        contract
+            .execute(deps.as_mut(), mock_env(), mock_info("secp", &[]), exec_msg)
+            .unwrap_err();
+
+        let signature: Signature = secp_key.sign_prehash(&hash("0")).unwrap();
+        let exec_msg = ExecuteMsg::Response {
+            token_id: "0".to_string(),
+            output: "MEOW".to_string(),
+            attestation: Some(Attestation {
+                oracle: "secp".to_string(),
+                signature: signature.to_bytes().to_vec().into(),
+            }),
+            generation: None,
+        };
+        // the attestation of another oracle can't be relayed
+        contract
+            .execute(
+                deps.as_mut(),
+                mock_env(),
+                mock_info("edwards", &[]),
+                exec_msg.clone(),
+            )
+            .unwrap_err();
+        contract
+            .execute(deps.as_mut(), mock_env(), mock_info("secp", &[]), exec_msg)
+            .unwrap();
+
+        let signature = ed_key.sign(&hash("1"));
+        let exec_msg = ExecuteMsg::Response {
+            token_id: "1".to_string(),
+            output: "MEOW".to_string(),
+            attestation: Some(Attestation {
+                oracle: "edwards".to_string(),
+                signature: <[u8; 64]>::from(signature).to_vec().into(),
+            }),
+            generation: None,
+        };
+        contract
+            .execute(
+                deps.as_mut(),
+                mock_env(),
+                mock_info("edwards", &[]),
+                exec_msg,
+            )
+            .unwrap();
+
+        for token_id in ["0", "1"] {
+            let query_msg: QueryMsg<Empty> = QueryMsg::Verification {
+                token_id: token_id.to_string(),
+            };
+            let res: VerificationResponse = from_json(
+                contract
+                    .query(deps.as_ref(), mock_env(), query_msg)
+                    .unwrap(),
+            )
+            .unwrap();
+            assert!(matches!(res.status, VerificationStatus::Verified { .. }));
+        }
+    }
+
+    /// a * b = c, with a and b public: the smallest circuit taking our two public inputs
+    struct ProductCircuit {
+        inputs: [ark_bn254::Fr; 2],
+    }
+
+    impl ark_relations::r1cs::ConstraintSynthesizer<ark_bn254::Fr> for ProductCircuit {
+        fn generate_constraints(
+            self,
+            cs: ark_relations::r1cs::ConstraintSystemRef<ark_bn254::Fr>,
+        ) -> ark_relations::r1cs::Result<()> {
+            use ark_relations::lc;
+
+            let [a, b] = self.inputs;
+            let a_var = cs.new_input_variable(|| Ok(a))?;
+            let b_var = cs.new_input_variable(|| Ok(b))?;
+            let c_var = cs.new_witness_variable(|| Ok(a * b))?;
+            cs.enforce_constraint(lc!() + a_var, lc!() + b_var, lc!() + c_var)
+        }
+    }
+
+    #[test]
+    fn verify_with_groth16_backend() {
+        use ark_groth16::Groth16;
+        use ark_serialize::CanonicalSerialize;
+
+        let mut deps = mock_dependencies();
+        let contract = Cw7007Contract::<Extension, Empty, Empty, Empty>::default();
+
+        let info = mock_info(CREATOR, &[]);
+        let prompt = "You are a cat. Just answer with 'MEOW'.".to_string();
+        let init_msg = InstantiateMsg {
+            prompt: prompt.clone(),
+            verifier: Some(VerifierConfig::Groth16 {}),
+            ..default_init()
+        };
+        contract
+            .instantiate(deps.as_mut(), mock_env(), info.clone(), init_msg)
+            .unwrap();
+        register_oracle(&contract, deps.as_mut(), CREATOR, None);
+
+        let exec_msg = ExecuteMsg::Mint {
+            token_id: "Not used".to_string(),
+            owner: "john".to_string(),
+            token_uri: None,
+            extension: Some(Metadata {
+                description: Some("Hello".into()),
+                ..Metadata::default()
+            }),
+            prompt: "Hello".to_string(),
+            variables: None,
+            callback: None,
+            prompt_id: None,
+        };
+        contract
+            .execute(deps.as_mut(), mock_env(), info.clone(), exec_msg)
+            .unwrap();
+        let exec_msg = ExecuteMsg::Response {
+            token_id: "0".to_string(),
+            output: "MEOW".to_string(),
+            attestation: None,
+            generation: None,
+        };
+        contract
+            .execute(deps.as_mut(), mock_env(), info.clone(), exec_msg)
+            .unwrap();
+
+        let rng = &mut ark_std::test_rng();
+        let inputs = VerificationInput {
+            prompt,
+            input: "Hello".into(),
+            seed: model::generation_seed(&mock_env(), "0"),
+            output: "MEOW".into(),
+        }
+        .public_inputs();
+        let pk = Groth16::<ark_bn254::Bn254>::generate_random_parameters_with_reduction(
+            ProductCircuit { inputs },
+            rng,
+        )
+        .unwrap();
+        let proof = |inputs| {
+            let proof = Groth16::<ark_bn254::Bn254>::create_random_proof_with_reduction(
+                ProductCircuit { inputs },
+                &pk,
+                &mut ark_std::test_rng(),
+            )
+            .unwrap();
+            let mut bytes = vec![];
+            proof.serialize_compressed(&mut bytes).unwrap();
+            cosmwasm_std::Binary::from(bytes).to_base64()
+        };
+        let mut vk = vec![];
+        pk.vk.serialize_compressed(&mut vk).unwrap();
+
+        // no verifying key yet
+        let verify_msg = ExecuteMsg::Verify {
+            token_id: "0".to_string(),
+            proof: proof(inputs),
+        };
+        contract
+            .execute(deps.as_mut(), mock_env(), info.clone(), verify_msg)
+            .unwrap_err();
+
+        let exec_msg = ExecuteMsg::SetVerifyingKey { vk: vk.into() };
+        contract
+            .execute(
+                deps.as_mut(),
+                mock_env(),
+                mock_info("john", &[]),
+                exec_msg.clone(),
+            )
+            .unwrap_err();
+        contract
+            .execute(deps.as_mut(), mock_env(), info.clone(), exec_msg)
+            .unwrap();
+
+        // a valid proof for other public inputs does not verify this token
+        let verify_msg = ExecuteMsg::Verify {
+            token_id: "0".to_string(),
+            proof: proof([inputs[0], inputs[0]]),
+        };
+        contract
+            .execute(deps.as_mut(), mock_env(), info.clone(), verify_msg)
+            .unwrap_err();
+
+        let verify_msg = ExecuteMsg::Verify {
+            token_id: "0".to_string(),
+            proof: proof(inputs),
+        };
+        contract
+            .execute(deps.as_mut(), mock_env(), info, verify_msg)
+            .unwrap();
+
+        let query_msg: QueryMsg<Empty> = QueryMsg::Verification {
+            token_id: "0".to_string(),
+        };
+        let res: VerificationResponse = from_json(
+            contract
+                .query(deps.as_ref(), mock_env(), query_msg)
+                .unwrap(),
+        )
+        .unwrap();
+        assert!(matches!(res.status, VerificationStatus::Verified { .. }));
+    }
+
+    #[test]
+    fn optimistic_response_challenge() {
+        let mut deps = mock_dependencies();
+        let contract = Cw7007Contract::<Extension, Empty, Empty, Empty>::default();
+
+        let info = mock_info(CREATOR, &[]);
+        let init_msg = InstantiateMsg {
+            optimistic: Some(OptimisticConfig {
+                challenge_window: 10,
+                challenge_bond: cosmwasm_std::coin(100, "uarch"),
+                arbiter: Some("judge".to_string()),
+            }),
+            ..default_init()
+        };
+        contract
+            .instantiate(deps.as_mut(), mock_env(), info.clone(), init_msg)
+            .unwrap();
+        register_oracle(&contract, deps.as_mut(), CREATOR, None);
+
+        let exec_msg = ExecuteMsg::Mint {
+            token_id: "Not used".to_string(),
+            owner: "john".to_string(),
+            token_uri: None,
+            extension: Some(Metadata {
+                description: Some("Hello".into()),
+                ..Metadata::default()
+            }),
+            prompt: "Hello".to_string(),
+            variables: None,
+            callback: None,
+            prompt_id: None,
+        };
+        contract
+            .execute(deps.as_mut(), mock_env(), info.clone(), exec_msg)
+            .unwrap();
+
+        let respond = |deps: DepsMut, output: &str| {
+            let exec_msg = ExecuteMsg::Response {
+                token_id: "0".to_string(),
+                output: output.to_string(),
+                attestation: None,
+                generation: None,
+            };
+            contract.execute(deps, mock_env(), mock_info(CREATOR, &[]), exec_msg)
+        };
+        let image = |deps: Deps| {
+            let query_msg: QueryMsg<Empty> = QueryMsg::NftInfo {
+                token_id: "0".to_string(),
+            };
+            let res: NftInfoResponse<Metadata> =
+                from_json(contract.query(deps, mock_env(), query_msg).unwrap()).unwrap();
+            res.extension.image
+        };
+        let env_at = |blocks: u64| {
+            let mut env = mock_env();
+            env.block.height += blocks;
+            env
+        };
+
+        respond(deps.as_mut(), "WOOF").unwrap();
+        assert_eq!(image(deps.as_ref()), None);
+        respond(deps.as_mut(), "MEOW").unwrap_err();
+
+        // wrong bond
+        let challenge = ExecuteMsg::ChallengeResponse {
+            token_id: "0".to_string(),
+        };
+        contract
+            .execute(
+                deps.as_mut(),
+                env_at(1),
+                mock_info("watcher", &cosmwasm_std::coins(50, "uarch")),
+                challenge.clone(),
+            )
+            .unwrap_err();
+        contract
+            .execute(
+                deps.as_mut(),
+                env_at(1),
+                mock_info("watcher", &cosmwasm_std::coins(100, "uarch")),
+                challenge,
+            )
+            .unwrap();
+
+        // only the arbiter resolves
+        let resolve = ExecuteMsg::ResolveChallenge {
+            token_id: "0".to_string(),
+            upheld: true,
+        };
+        contract
+            .execute(deps.as_mut(), env_at(2), info.clone(), resolve.clone())
+            .unwrap_err();
+        let res = contract
+            .execute(deps.as_mut(), env_at(2), mock_info("judge", &[]), resolve)
+            .unwrap();
+        assert_eq!(
+            res.messages[0].msg,
+            cosmwasm_std::BankMsg::Send {
+                to_address: "watcher".to_string(),
+                amount: cosmwasm_std::coins(100, "uarch"),
+            }
+            .into()
+        );
+        assert_eq!(image(deps.as_ref()), None);
+
+        // unchallenged proposals finalize once the window is over
+        respond(deps.as_mut(), "MEOW").unwrap();
+        let query_msg: QueryMsg<Empty> = QueryMsg::Proposal {
+            token_id: "0".to_string(),
+        };
+        let proposal: Option<Proposal> = from_json(
+            contract
+                .query(deps.as_ref(), mock_env(), query_msg)
+                .unwrap(),
+        )
+        .unwrap();
+        assert_eq!(
+            proposal.unwrap().challenge_deadline,
+            env_at(10).block.height
+        );
+
+        let finalize = ExecuteMsg::FinalizeResponse {
+            token_id: "0".to_string(),
+        };
+        contract
+            .execute(
+                deps.as_mut(),
+                env_at(10),
+                mock_info("anyone", &[]),
+                finalize.clone(),
+            )
+            .unwrap_err();
+        contract
+            .execute(
+                deps.as_mut(),
+                env_at(11),
+                mock_info("anyone", &[]),
+                finalize,
+            )
+            .unwrap();
+        assert_eq!(image(deps.as_ref()), Some("MEOW".to_string()));
+    }
+
+    #[test]
+    fn request_queue_pagination() {
+        let mut deps = mock_dependencies();
+        let contract = Cw7007Contract::<Extension, Empty, Empty, Empty>::default();
+
+        let info = mock_info(CREATOR, &[]);
+        let init_msg = default_init();
+        contract
+            .instantiate(deps.as_mut(), mock_env(), info.clone(), init_msg)
+            .unwrap();
+        register_oracle(&contract, deps.as_mut(), CREATOR, None);
+
+        for _ in 0..4 {
+            let exec_msg = ExecuteMsg::Mint {
+                token_id: "Not used".to_string(),
+                owner: "john".to_string(),
+                token_uri: None,
+                extension: Some(Metadata {
+                    description: Some("Hello".into()),
+                    ..Metadata::default()
+                }),
+                prompt: "Hello".to_string(),
+                variables: None,
+                callback: None,
+                prompt_id: None,
+            };
+            contract
+                .execute(deps.as_mut(), mock_env(), mock_info("john", &[]), exec_msg)
+                .unwrap();
+        }
+
+        let exec_msg = ExecuteMsg::Response {
+            token_id: "1".to_string(),
+            output: "MEOW".to_string(),
+            attestation: None,
+            generation: None,
+        };
+        contract
+            .execute(deps.as_mut(), mock_env(), info.clone(), exec_msg.clone())
+            .unwrap();
+        // a fulfilled request cannot be answered twice
+        contract
+            .execute(deps.as_mut(), mock_env(), info, exec_msg)
+            .unwrap_err();
+
+        let request_ids = |start_after: Option<&str>, limit| {
+            let query_msg: QueryMsg<Empty> = QueryMsg::RequestIds {
+                start_after: start_after.map(String::from),
+                limit: Some(limit),
+            };
+            let res: RequestIdsResponse = from_json(
+                contract
+                    .query(deps.as_ref(), mock_env(), query_msg)
+                    .unwrap(),
+            )
+            .unwrap();
+            res.ids
+        };
+        assert_eq!(request_ids(None, 2), vec!["0", "2"]);
+        assert_eq!(request_ids(Some("2"), 2), vec!["3"]);
+        assert_eq!(request_ids(Some("3"), 2), Vec::<String>::new());
+    }
+
+    #[test]
+    fn request_lifecycle() {
+        let mut deps = mock_dependencies();
+        let contract = Cw7007Contract::<Extension, Empty, Empty, Empty>::default();
+
+        let info = mock_info(CREATOR, &[]);
+        let init_msg = InstantiateMsg {
+            request_timeout: Some(cw_utils::Duration::Height(5)),
+            ..default_init()
+        };
+        contract
+            .instantiate(deps.as_mut(), mock_env(), info.clone(), init_msg)
+            .unwrap();
+        register_oracle(&contract, deps.as_mut(), CREATOR, None);
+
+        for _ in 0..3 {
+            let exec_msg = ExecuteMsg::Mint {
+                token_id: "Not used".to_string(),
+                owner: "john".to_string(),
+                token_uri: None,
+                extension: Some(Metadata {
+                    description: Some("Hello".into()),
+                    ..Metadata::default()
+                }),
+                prompt: "Hello".to_string(),
+                variables: None,
+                callback: None,
+                prompt_id: None,
+            };
+            contract
+                .execute(deps.as_mut(), mock_env(), mock_info("john", &[]), exec_msg)
+                .unwrap();
+        }
+        let request = |deps: Deps, token_id: &str| {
+            let query_msg: QueryMsg<Empty> = QueryMsg::Request {
+                token_id: token_id.to_string(),
+            };
+            let res: Request =
+                from_json(contract.query(deps, mock_env(), query_msg).unwrap()).unwrap();
+            res
+        };
+        assert_eq!(request(deps.as_ref(), "0").status, RequestStatus::Pending);
+
+        let mut env = mock_env();
+        env.block.height += 5;
+
+        let exec_msg = ExecuteMsg::FailRequest {
+            token_id: "0".to_string(),
+            reason: "content policy".to_string(),
+        };
+        contract
+            .execute(
+                deps.as_mut(),
+                env.clone(),
+                mock_info("john", &[]),
+                exec_msg.clone(),
+            )
+            .unwrap_err();
+        contract
+            .execute(deps.as_mut(), env.clone(), info.clone(), exec_msg.clone())
+            .unwrap();
+        // final statuses are final
+        contract
+            .execute(deps.as_mut(), env.clone(), info.clone(), exec_msg)
+            .unwrap_err();
+
+        let exec_msg = ExecuteMsg::CancelRequest {
+            token_id: "1".to_string(),
+        };
+        contract
+            .execute(deps.as_mut(), env.clone(), info.clone(), exec_msg.clone())
+            .unwrap_err();
+        contract
+            .execute(deps.as_mut(), env.clone(), mock_info("john", &[]), exec_msg)
+            .unwrap();
+
+        let exec_msg = ExecuteMsg::Response {
+            token_id: "1".to_string(),
+            output: "MEOW".to_string(),
+            attestation: None,
+            generation: None,
+        };
+        contract
+            .execute(deps.as_mut(), env.clone(), info, exec_msg)
+            .unwrap_err();
+
+        let failed = request(deps.as_ref(), "0");
+        assert_eq!(
+            failed.status,
+            RequestStatus::Failed {
+                reason: "content policy".to_string()
+            }
+        );
+        assert_eq!(failed.transitions.len(), 2);
+        assert_eq!(failed.transitions[0].height, mock_env().block.height);
+        assert_eq!(failed.transitions[1].height, env.block.height);
+        assert_eq!(request(deps.as_ref(), "1").status, RequestStatus::Cancelled);
+        assert_eq!(request(deps.as_ref(), "2").status, RequestStatus::Pending);
+    }
+
+    #[test]
+    fn expire_request_refunds_requester() {
+        let mut deps = mock_dependencies();
+        let contract = Cw7007Contract::<Extension, Empty, Empty, Empty>::default();
+
+        let info = mock_info(CREATOR, &[]);
+        let init_msg = InstantiateMsg {
+            request_timeout: Some(cw_utils::Duration::Height(10)),
+            mint_fee: Some(MintFeeConfig {
+                price: cosmwasm_std::coins(5, "uarch"),
+                treasury: None,
+                treasury_share: cosmwasm_std::Decimal::zero(),
+            }),
+            ..default_init()
+        };
+        contract
+            .instantiate(deps.as_mut(), mock_env(), info.clone(), init_msg)
+            .unwrap();
+        register_oracle(&contract, deps.as_mut(), CREATOR, None);
+
+        let exec_msg = ExecuteMsg::Mint {
+            token_id: "Not used".to_string(),
+            owner: "john".to_string(),
+            token_uri: None,
+            extension: Some(Metadata {
+                description: Some("Hello".into()),
+                ..Metadata::default()
+            }),
+            prompt: "Hello".to_string(),
+            variables: None,
+            callback: None,
+            prompt_id: None,
+        };
+        contract
+            .execute(
+                deps.as_mut(),
+                mock_env(),
+                mock_info("john", &cosmwasm_std::coins(5, "uarch")),
+                exec_msg,
+            )
+            .unwrap();
+
+        let mut env = mock_env();
+        env.block.height += 9;
+        let exec_msg = ExecuteMsg::ExpireRequest {
+            token_id: "0".to_string(),
+        };
+        contract
+            .execute(
+                deps.as_mut(),
+                env.clone(),
+                mock_info("anyone", &[]),
+                exec_msg.clone(),
+            )
+            .unwrap_err();
+
+        env.block.height += 1;
+        let response_msg = ExecuteMsg::Response {
+            token_id: "0".to_string(),
+            output: "MEOW".to_string(),
+            attestation: None,
+            generation: None,
+        };
+        contract
+            .execute(deps.as_mut(), env.clone(), info, response_msg)
+            .unwrap_err();
+
+        let res = contract
+            .execute(deps.as_mut(), env, mock_info("anyone", &[]), exec_msg)
+            .unwrap();
+        assert_eq!(
+            res.messages[0].msg,
+            cosmwasm_std::BankMsg::Send {
+                to_address: "john".to_string(),
+                amount: cosmwasm_std::coins(5, "uarch"),
+            }
+            .into()
+        );
+
+        let query_msg: QueryMsg<Empty> = QueryMsg::Request {
+            token_id: "0".to_string(),
+        };
+        let res: Request = from_json(
+            contract
+                .query(deps.as_ref(), mock_env(), query_msg)
+                .unwrap(),
+        )
+        .unwrap();
+        assert_eq!(res.status, RequestStatus::Expired);
+    }
+
+    #[test]
+    fn mint_fee_escrow() {
+        let mut deps = mock_dependencies();
+        let contract = Cw7007Contract::<Extension, Empty, Empty, Empty>::default();
+
+        let info = mock_info(CREATOR, &[]);
+        let init_msg = InstantiateMsg {
+            request_timeout: Some(cw_utils::Duration::Height(10)),
+            mint_fee: Some(MintFeeConfig {
+                price: cosmwasm_std::coins(100, "uarch"),
+                treasury: Some("treasury".to_string()),
+                treasury_share: cosmwasm_std::Decimal::percent(20),
+            }),
+            ..default_init()
+        };
+        contract
+            .instantiate(deps.as_mut(), mock_env(), info.clone(), init_msg)
+            .unwrap();
+        register_oracle(&contract, deps.as_mut(), CREATOR, None);
+
+        let mint = |deps: DepsMut, funds: &[cosmwasm_std::Coin]| {
+            let exec_msg = ExecuteMsg::Mint {
+                token_id: "Not used".to_string(),
+                owner: "john".to_string(),
+                token_uri: None,
+                extension: Some(Metadata {
+                    description: Some("Hello".into()),
+                    ..Metadata::default()
+                }),
+                prompt: "Hello".to_string(),
+                variables: None,
+                callback: None,
+                prompt_id: None,
+            };
+            contract.execute(deps, mock_env(), mock_info("john", funds), exec_msg)
+        };
+        mint(deps.as_mut(), &[]).unwrap_err();
+        mint(deps.as_mut(), &cosmwasm_std::coins(99, "uarch")).unwrap_err();
+        mint(deps.as_mut(), &cosmwasm_std::coins(100, "uarch")).unwrap();
+        mint(deps.as_mut(), &cosmwasm_std::coins(100, "uarch")).unwrap();
+
+        // a cancel landing before the pending response can't take the fee back
+        let exec_msg = ExecuteMsg::CancelRequest {
+            token_id: "0".to_string(),
+        };
+        contract
+            .execute(deps.as_mut(), mock_env(), mock_info("john", &[]), exec_msg)
+            .unwrap_err();
+
+        let exec_msg = ExecuteMsg::Response {
+            token_id: "0".to_string(),
+            output: "MEOW".to_string(),
+            attestation: None,
+            generation: None,
+        };
+        let res = contract
+            .execute(deps.as_mut(), mock_env(), info.clone(), exec_msg)
+            .unwrap();
+        assert_eq!(
+            res.messages
+                .into_iter()
+                .map(|m| m.msg)
+                .collect::<Vec<cosmwasm_std::CosmosMsg>>(),
+            vec![
+                cosmwasm_std::BankMsg::Send {
+                    to_address: CREATOR.to_string(),
+                    amount: cosmwasm_std::coins(80, "uarch"),
+                }
+                .into(),
+                cosmwasm_std::BankMsg::Send {
+                    to_address: "treasury".to_string(),
+                    amount: cosmwasm_std::coins(20, "uarch"),
+                }
+                .into(),
+            ]
+        );
+
+        // failed requests are refunded in full
+        let exec_msg = ExecuteMsg::FailRequest {
+            token_id: "1".to_string(),
+            reason: "busy".to_string(),
+        };
+        let res = contract
+            .execute(deps.as_mut(), mock_env(), info.clone(), exec_msg)
+            .unwrap();
+        assert_eq!(
+            res.messages[0].msg,
+            cosmwasm_std::BankMsg::Send {
+                to_address: "john".to_string(),
+                amount: cosmwasm_std::coins(100, "uarch"),
+            }
+            .into()
+        );
+
+        // only the owner updates the fee
+        mint(deps.as_mut(), &cosmwasm_std::coins(100, "uarch")).unwrap();
+        let exec_msg = ExecuteMsg::UpdateMintFee { mint_fee: None };
+        contract
+            .execute(
+                deps.as_mut(),
+                mock_env(),
+                mock_info("john", &[]),
+                exec_msg.clone(),
+            )
+            .unwrap_err();
+        contract
+            .execute(deps.as_mut(), mock_env(), info.clone(), exec_msg)
+            .unwrap();
+        mint(deps.as_mut(), &[]).unwrap();
+
+        // a request keeps the treasury cut it was minted with
+        let exec_msg = ExecuteMsg::Response {
+            token_id: "2".to_string(),
+            output: "MEOW".to_string(),
+            attestation: None,
+            generation: None,
+        };
+        let res = contract
+            .execute(deps.as_mut(), mock_env(), info, exec_msg)
+            .unwrap();
+        assert_eq!(
+            res.messages
+                .into_iter()
+                .map(|m| m.msg)
+                .collect::<Vec<cosmwasm_std::CosmosMsg>>(),
+            vec![
+                cosmwasm_std::BankMsg::Send {
+                    to_address: CREATOR.to_string(),
+                    amount: cosmwasm_std::coins(80, "uarch"),
+                }
+                .into(),
+                cosmwasm_std::BankMsg::Send {
+                    to_address: "treasury".to_string(),
+                    amount: cosmwasm_std::coins(20, "uarch"),
+                }
+                .into(),
+            ]
+        );
+    }
+
+    #[test]
+    fn burn_refunds_pending_request() {
+        let mut deps = mock_dependencies();
+        let contract = Cw7007Contract::<Extension, Empty, Empty, Empty>::default();
+
+        // no timeout nor assignment, so the burn is the only way back for the fee
+        let init_msg = InstantiateMsg {
+            mint_fee: Some(MintFeeConfig {
+                price: cosmwasm_std::coins(100, "uarch"),
+                treasury: Some("treasury".to_string()),
+                treasury_share: cosmwasm_std::Decimal::percent(20),
+            }),
+            ..default_init()
+        };
+        contract
+            .instantiate(deps.as_mut(), mock_env(), mock_info(CREATOR, &[]), init_msg)
+            .unwrap();
+        let exec_msg = ExecuteMsg::Mint {
+            token_id: "Not used".to_string(),
+            owner: "john".to_string(),
+            token_uri: None,
+            extension: None,
+            prompt: "Hello".to_string(),
+            variables: None,
+            callback: None,
+            prompt_id: None,
+        };
+        contract
+            .execute(
+                deps.as_mut(),
+                mock_env(),
+                mock_info("john", &cosmwasm_std::coins(100, "uarch")),
+                exec_msg,
+            )
+            .unwrap();
+
+        let burn = ExecuteMsg::Burn {
+            token_id: "0".to_string(),
+        };
+        contract
+            .execute(
+                deps.as_mut(),
+                mock_env(),
+                mock_info("jane", &[]),
+                burn.clone(),
+            )
+            .unwrap_err();
+        let res = contract
+            .execute(deps.as_mut(), mock_env(), mock_info("john", &[]), burn)
+            .unwrap();
+        assert_eq!(
+            res.messages[0].msg,
+            cosmwasm_std::BankMsg::Send {
+                to_address: "john".to_string(),
+                amount: cosmwasm_std::coins(100, "uarch"),
+            }
+            .into()
+        );
+        let query_msg: QueryMsg<Empty> = QueryMsg::Request {
+            token_id: "0".to_string(),
+        };
+        let request: Request = from_json(
+            contract
+                .query(deps.as_ref(), mock_env(), query_msg)
+                .unwrap(),
+        )
+        .unwrap();
+        assert_eq!(request.status, RequestStatus::Cancelled);
+    }
+
+    #[test]
+    fn cw20_mint_payment() {
+        let mut deps = mock_dependencies();
+        let contract = Cw7007Contract::<Extension, Empty, Empty, Empty>::default();
+
+        let info = mock_info(CREATOR, &[]);
+        let init_msg = InstantiateMsg {
+            mint_fee: Some(MintFeeConfig {
+                price: cosmwasm_std::coins(100, "uarch"),
+                treasury: Some("treasury".to_string()),
+                treasury_share: cosmwasm_std::Decimal::percent(20),
+            }),
+            ..default_init()
+        };
+        contract
+            .instantiate(deps.as_mut(), mock_env(), info.clone(), init_msg)
+            .unwrap();
+        register_oracle(&contract, deps.as_mut(), CREATOR, None);
+
+        let send = |deps: DepsMut, token: &str, amount: u128| {
+            let msg = ReceiveMsg::Mint(MintMsg {
+                token_id: None,
+                owner: "john".to_string(),
+                token_uri: None,
+                extension: Some(Metadata {
+                    description: Some("Hello".into()),
+                    ..Metadata::default()
+                }),
+                prompt: "Hello".to_string(),
+                variables: None,
+                callback: None,
+                prompt_id: None,
+            });
+            let exec_msg = ExecuteMsg::Receive(cw20::Cw20ReceiveMsg {
+                sender: "john".to_string(),
+                amount: amount.into(),
+                msg: cosmwasm_std::to_json_binary(&msg).unwrap(),
+            });
+            contract.execute(deps, mock_env(), mock_info(token, &[]), exec_msg)
+        };
+
+        // tokens have to be whitelisted by the owner first
+        send(deps.as_mut(), "usdc", 50).unwrap_err();
+        let exec_msg = ExecuteMsg::UpdateCw20Price {
+            token: "usdc".to_string(),
+            price: Some(50u128.into()),
+        };
+        contract
+            .execute(
+                deps.as_mut(),
+                mock_env(),
+                mock_info("john", &[]),
+                exec_msg.clone(),
+            )
+            .unwrap_err();
+        contract
+            .execute(deps.as_mut(), mock_env(), info.clone(), exec_msg)
+            .unwrap();
+
+        send(deps.as_mut(), "usdc", 49).unwrap_err();
+        send(deps.as_mut(), "usdc", 50).unwrap();
+        send(deps.as_mut(), "usdc", 50).unwrap();
+        let request: Request = from_json(
+            contract
+                .query(
+                    deps.as_ref(),
+                    mock_env(),
+                    QueryMsg::Request {
+                        token_id: "0".to_string(),
+                    },
+                )
+                .unwrap(),
+        )
+        .unwrap();
+        assert_eq!(request.requester, "john");
+
+        let transfer = |recipient: &str, amount: u128| -> cosmwasm_std::CosmosMsg {
+            cosmwasm_std::WasmMsg::Execute {
+                contract_addr: "usdc".to_string(),
+                msg: cosmwasm_std::to_json_binary(&cw20::Cw20ExecuteMsg::Transfer {
+                    recipient: recipient.to_string(),
+                    amount: amount.into(),
+                })
+                .unwrap(),
+                funds: vec![],
+            }
+            .into()
+        };
+
+        let exec_msg = ExecuteMsg::Response {
+            token_id: "0".to_string(),
+            output: "MEOW".to_string(),
+            attestation: None,
+            generation: None,
+        };
+        let res = contract
+            .execute(deps.as_mut(), mock_env(), info.clone(), exec_msg)
+            .unwrap();
+        assert_eq!(
+            res.messages
+                .into_iter()
+                .map(|m| m.msg)
+                .collect::<Vec<cosmwasm_std::CosmosMsg>>(),
+            vec![transfer(CREATOR, 40), transfer("treasury", 10)]
+        );
+
+        // refunds go back in the token that was paid
+        let exec_msg = ExecuteMsg::FailRequest {
+            token_id: "1".to_string(),
+            reason: "busy".to_string(),
+        };
+        let res = contract
+            .execute(deps.as_mut(), mock_env(), info, exec_msg)
+            .unwrap();
+        assert_eq!(res.messages[0].msg, transfer("john", 50));
+    }
+
+    #[test]
+    fn oracle_registry() {
+        let mut deps = mock_dependencies();
+        let contract = Cw7007Contract::<Extension, Empty, Empty, Empty>::default();
+
+        let info = mock_info(CREATOR, &[]);
+        let init_msg = default_init();
+        contract
+            .instantiate(deps.as_mut(), mock_env(), info.clone(), init_msg)
+            .unwrap();
+
+        for _ in 0..2 {
+            let exec_msg = ExecuteMsg::Mint {
+                token_id: "Not used".to_string(),
+                owner: "john".to_string(),
+                token_uri: None,
+                extension: Some(Metadata {
+                    description: Some("Hello".into()),
+                    ..Metadata::default()
+                }),
+                prompt: "Hello".to_string(),
+                variables: None,
+                callback: None,
+                prompt_id: None,
+            };
+            contract
+                .execute(deps.as_mut(), mock_env(), info.clone(), exec_msg)
+                .unwrap();
+        }
+
+        let respond = |deps: DepsMut, sender: &str, token_id: &str| {
+            let exec_msg = ExecuteMsg::Response {
+                token_id: token_id.to_string(),
+                output: "MEOW".to_string(),
+                attestation: None,
+                generation: None,
+            };
+            contract.execute(deps, mock_env(), mock_info(sender, &[]), exec_msg)
+        };
+        let set_active = |deps: DepsMut, sender: &str, active: bool| {
+            let exec_msg = if active {
+                ExecuteMsg::ResumeOracle {
+                    oracle: "gpu".to_string(),
+                }
+            } else {
+                ExecuteMsg::PauseOracle {
+                    oracle: "gpu".to_string(),
+                }
+            };
+            contract.execute(deps, mock_env(), mock_info(sender, &[]), exec_msg)
+        };
+
+        // owning the contract is not enough to respond
+        respond(deps.as_mut(), CREATOR, "0").unwrap_err();
+
+        // only the owner registers oracles
+        let exec_msg = ExecuteMsg::RegisterOracle {
+            oracle: "gpu".to_string(),
+            metadata: OracleMetadata {
+                name: "GPU farm".to_string(),
+                endpoint: "https://gpu.example.com".to_string(),
+                key: None,
+                models: vec!["llama3".to_string(), "sdxl".to_string()],
+            },
+        };
+        contract
+            .execute(
+                deps.as_mut(),
+                mock_env(),
+                mock_info("gpu", &[]),
+                exec_msg.clone(),
+            )
+            .unwrap_err();
+        contract
+            .execute(deps.as_mut(), mock_env(), info.clone(), exec_msg)
+            .unwrap();
+        register_oracle(&contract, deps.as_mut(), "cpu", None);
+
+        set_active(deps.as_mut(), "gpu", false).unwrap_err();
+        set_active(deps.as_mut(), CREATOR, false).unwrap();
+        respond(deps.as_mut(), "gpu", "0").unwrap_err();
+        set_active(deps.as_mut(), CREATOR, true).unwrap();
+        respond(deps.as_mut(), "gpu", "0").unwrap();
+
+        let query_msg: QueryMsg<Empty> = QueryMsg::Oracle {
+            address: "gpu".to_string(),
+        };
+        let oracle: Option<Oracle> = from_json(
+            contract
+                .query(deps.as_ref(), mock_env(), query_msg)
+                .unwrap(),
+        )
+        .unwrap();
+        let oracle = oracle.unwrap();
+        assert!(oracle.active);
+        assert_eq!(oracle.metadata.models, vec!["llama3", "sdxl"]);
+
+        let query_msg: QueryMsg<Empty> = QueryMsg::Oracles {
+            start_after: Some("cpu".to_string()),
+            limit: None,
+        };
+        let res: OraclesResponse = from_json(
+            contract
+                .query(deps.as_ref(), mock_env(), query_msg)
+                .unwrap(),
+        )
+        .unwrap();
+        assert_eq!(res.oracles.len(), 1);
+        assert_eq!(res.oracles[0].address, "gpu");
+
+        let exec_msg = ExecuteMsg::RemoveOracle {
+            oracle: "gpu".to_string(),
+        };
+        contract
+            .execute(deps.as_mut(), mock_env(), info, exec_msg)
+            .unwrap();
+        respond(deps.as_mut(), "gpu", "1").unwrap_err();
+        respond(deps.as_mut(), "cpu", "1").unwrap();
+    }
+
+    #[test]
+    fn round_robin_assignment() {
+        let mut deps = mock_dependencies();
+        let contract = Cw7007Contract::<Extension, Empty, Empty, Empty>::default();
+
+        let info = mock_info(CREATOR, &[]);
+        let init_msg = InstantiateMsg {
+            assignment: Some(AssignmentConfig {
+                strategy: AssignmentStrategy::RoundRobin {},
+                timeout: cw_utils::Duration::Height(5),
+            }),
+            ..default_init()
+        };
+        contract
+            .instantiate(deps.as_mut(), mock_env(), info.clone(), init_msg)
+            .unwrap();
+        for oracle in ["aaa", "bbb", "ccc"] {
+            register_oracle(&contract, deps.as_mut(), oracle, None);
+        }
+
+        let mint = |deps: DepsMut| {
+            let exec_msg = ExecuteMsg::Mint {
+                token_id: "Not used".to_string(),
+                owner: "john".to_string(),
+                token_uri: None,
+                extension: Some(Metadata {
+                    description: Some("Hello".into()),
+                    ..Metadata::default()
+                }),
+                prompt: "Hello".to_string(),
+                variables: None,
+                callback: None,
+                prompt_id: None,
+            };
+            contract
+                .execute(deps, mock_env(), mock_info(CREATOR, &[]), exec_msg)
+                .unwrap();
+        };
+        for _ in 0..4 {
+            mint(deps.as_mut());
+        }
+        let assignee = |deps: Deps, token_id: &str| {
+            let query_msg: QueryMsg<Empty> = QueryMsg::Request {
+                token_id: token_id.to_string(),
+            };
+            let request: Request =
+                from_json(contract.query(deps, mock_env(), query_msg).unwrap()).unwrap();
+            request.assignee.unwrap()
+        };
+        for (token_id, oracle) in [("0", "aaa"), ("1", "bbb"), ("2", "ccc"), ("3", "aaa")] {
+            assert_eq!(assignee(deps.as_ref(), token_id), oracle);
+        }
+
+        let query_msg: QueryMsg<Empty> = QueryMsg::AssignedRequests {
+            oracle: "aaa".to_string(),
+            start_after: None,
+            limit: None,
+        };
+        let res: RequestIdsResponse = from_json(
+            contract
+                .query(deps.as_ref(), mock_env(), query_msg)
+                .unwrap(),
+        )
+        .unwrap();
+        assert_eq!(res.ids, vec!["0", "3"]);
+
+        // only the assignee gives up on its request
+        let exec_msg = ExecuteMsg::FailRequest {
+            token_id: "0".to_string(),
+            reason: "busy".to_string(),
+        };
+        contract
+            .execute(
+                deps.as_mut(),
+                mock_env(),
+                mock_info("bbb", &[]),
+                exec_msg.clone(),
+            )
+            .unwrap_err();
+        contract
+            .execute(deps.as_mut(), mock_env(), mock_info("aaa", &[]), exec_msg)
+            .unwrap();
+
+        // closed requests leave the queue of their oracle
+        let query_msg: QueryMsg<Empty> = QueryMsg::AssignedRequests {
+            oracle: "aaa".to_string(),
+            start_after: None,
+            limit: Some(1),
+        };
+        let res: RequestIdsResponse = from_json(
+            contract
+                .query(deps.as_ref(), mock_env(), query_msg)
+                .unwrap(),
+        )
+        .unwrap();
+        assert_eq!(res.ids, vec!["3"]);
+
+        let respond = |deps: DepsMut, env: cosmwasm_std::Env, sender: &str| {
+            let exec_msg = ExecuteMsg::Response {
+                token_id: "1".to_string(),
+                output: "MEOW".to_string(),
+                attestation: None,
+                generation: None,
+            };
+            contract.execute(deps, env, mock_info(sender, &[]), exec_msg)
+        };
+        respond(deps.as_mut(), mock_env(), "aaa").unwrap_err();
+
+        // the assignee has until its timeout before the request moves on
+        let mut later = mock_env();
+        later.block.height += 5;
+        let reassign = ExecuteMsg::ReassignRequest {
+            token_id: "1".to_string(),
+        };
+        contract
+            .execute(
+                deps.as_mut(),
+                mock_env(),
+                mock_info("john", &[]),
+                reassign.clone(),
+            )
+            .unwrap_err();
+        contract
+            .execute(
+                deps.as_mut(),
+                later.clone(),
+                mock_info("john", &[]),
+                reassign,
+            )
+            .unwrap();
+        assert_eq!(assignee(deps.as_ref(), "1"), "ccc");
+        respond(deps.as_mut(), later.clone(), "bbb").unwrap_err();
+        respond(deps.as_mut(), later, "ccc").unwrap();
+
+        // removed oracles leave the rotation
+        let exec_msg = ExecuteMsg::RemoveOracle {
+            oracle: "aaa".to_string(),
+        };
+        contract
+            .execute(deps.as_mut(), mock_env(), info, exec_msg)
+            .unwrap();
+        mint(deps.as_mut());
+        mint(deps.as_mut());
+        assert_eq!(assignee(deps.as_ref(), "4"), "bbb");
+        assert_eq!(assignee(deps.as_ref(), "5"), "ccc");
+    }
+
+    /// Oracles "aaa" and "bbb" bond 100 and 900, "ccc" stays below the minimum with 50
+    fn staked_assignment(
+        contract: &Cw7007Contract<Extension, Empty, Empty, Empty>,
+        mut deps: DepsMut,
+        strategy: AssignmentStrategy,
+    ) {
+        let init_msg = InstantiateMsg {
+            staking: Some(StakingConfig {
+                denom: "uarch".to_string(),
+                min_stake: 100u128.into(),
+                unbonding_period: cw_utils::Duration::Height(10),
+                dispute_slash: cosmwasm_std::Decimal::percent(50),
+            }),
+            assignment: Some(AssignmentConfig {
+                strategy,
+                timeout: cw_utils::Duration::Height(5),
+            }),
+            ..default_init()
+        };
+        contract
+            .instantiate(deps.branch(), mock_env(), mock_info(CREATOR, &[]), init_msg)
+            .unwrap();
+        for (oracle, amount) in [("aaa", 100), ("bbb", 900), ("ccc", 50)] {
+            register_oracle(contract, deps.branch(), oracle, None);
+            bond(contract, deps.branch(), oracle, amount);
+        }
+    }
+
+    fn bond(
+        contract: &Cw7007Contract<Extension, Empty, Empty, Empty>,
+        deps: DepsMut,
+        oracle: &str,
+        amount: u128,
+    ) {
+        let exec_msg = ExecuteMsg::Bond { metadata: None };
+        contract
+            .execute(
+                deps,
+                mock_env(),
+                mock_info(oracle, &coins(amount, "uarch")),
+                exec_msg,
+            )
+            .unwrap();
+    }
+
+    /// Mint `count` tokens, returning how many of them each oracle was assigned
+    fn count_assignees(
+        contract: &Cw7007Contract<Extension, Empty, Empty, Empty>,
+        mut deps: DepsMut,
+        count: usize,
+    ) -> std::collections::BTreeMap<String, usize> {
+        let mut assignees = std::collections::BTreeMap::new();
+        for _ in 0..count {
+            let exec_msg = ExecuteMsg::Mint {
+                token_id: "Not used".to_string(),
+                owner: "john".to_string(),
+                token_uri: None,
+                extension: None,
+                prompt: "Hello".to_string(),
+                variables: None,
+                callback: None,
+                prompt_id: None,
+            };
+            let res = contract
+                .execute(deps.branch(), mock_env(), mock_info("john", &[]), exec_msg)
+                .unwrap();
+            let assignee = res
+                .attributes
+                .into_iter()
+                .find(|attr| attr.key == "assignee")
+                .unwrap()
+                .value;
+            *assignees.entry(assignee).or_default() += 1;
+        }
+        assignees
+    }
+
+    #[test]
+    fn stake_weighted_assignment() {
+        let mut deps = mock_dependencies();
+        let contract = Cw7007Contract::<Extension, Empty, Empty, Empty>::default();
+        staked_assignment(
+            &contract,
+            deps.as_mut(),
+            AssignmentStrategy::StakeWeighted {},
+        );
+
+        // picks follow the stake, and skip oracles below the minimum
+        let assignees = count_assignees(&contract, deps.as_mut(), 200);
+        assert!(!assignees.contains_key("ccc"));
+        assert!((5..=40).contains(&assignees["aaa"]), "{:?}", assignees);
+        assert_eq!(assignees["aaa"] + assignees["bbb"], 200);
+
+        // unbonding drops the weight right away, bonding brings an oracle in
+        let exec_msg = ExecuteMsg::Unbond {
+            amount: 900u128.into(),
+        };
+        contract
+            .execute(deps.as_mut(), mock_env(), mock_info("bbb", &[]), exec_msg)
+            .unwrap();
+        bond(&contract, deps.as_mut(), "ccc", 50);
+        let assignees = count_assignees(&contract, deps.as_mut(), 100);
+        assert!(!assignees.contains_key("bbb"));
+        assert!((25..=75).contains(&assignees["aaa"]), "{:?}", assignees);
+        assert_eq!(assignees["aaa"] + assignees["ccc"], 100);
+
+        // slashed under the minimum, an oracle is out of the draw
+        let exec_msg = ExecuteMsg::Slash {
+            oracle: "ccc".to_string(),
+            amount: 1u128.into(),
+        };
+        contract
+            .execute(deps.as_mut(), mock_env(), mock_info(CREATOR, &[]), exec_msg)
+            .unwrap();
+        let assignees = count_assignees(&contract, deps.as_mut(), 10);
+        assert_eq!(assignees["aaa"], 10);
+    }
+
+    #[test]
+    fn random_assignment() {
+        let mut deps = mock_dependencies();
+        let contract = Cw7007Contract::<Extension, Empty, Empty, Empty>::default();
+        staked_assignment(&contract, deps.as_mut(), AssignmentStrategy::Random {});
+
+        // uniform among the oracles with enough stake, however much they bonded
+        let assignees = count_assignees(&contract, deps.as_mut(), 200);
+        assert!(!assignees.contains_key("ccc"));
+        assert!((70..=130).contains(&assignees["aaa"]), "{:?}", assignees);
+        assert_eq!(assignees["aaa"] + assignees["bbb"], 200);
+
+        // paused oracles leave the draw, reassignment skips the previous assignee
+        let exec_msg = ExecuteMsg::PauseOracle {
+            oracle: "bbb".to_string(),
+        };
+        contract
+            .execute(deps.as_mut(), mock_env(), mock_info(CREATOR, &[]), exec_msg)
+            .unwrap();
+        bond(&contract, deps.as_mut(), "ccc", 50);
+        let assignees = count_assignees(&contract, deps.as_mut(), 100);
+        assert!(!assignees.contains_key("bbb"));
+        assert_eq!(assignees["aaa"] + assignees["ccc"], 100);
+
+        let mut later = mock_env();
+        later.block.height += 5;
+        let exec_msg = ExecuteMsg::ReassignRequest {
+            token_id: "200".to_string(),
+        };
+        let previous = contract
+            .requests
+            .load(&deps.storage, "200")
+            .unwrap()
+            .assignee
+            .unwrap();
+        contract
+            .execute(deps.as_mut(), later, mock_info("john", &[]), exec_msg)
+            .unwrap();
+        let assignee = contract
+            .requests
+            .load(&deps.storage, "200")
+            .unwrap()
+            .assignee
+            .unwrap();
+        assert_ne!(assignee, previous);
+    }
+
+    #[test]
+    fn quorum_agreement() {
+        use sha2::{Digest, Sha256};
+
+        let mut deps = mock_dependencies();
+        let contract = Cw7007Contract::<Extension, Empty, Empty, Empty>::default();
+
+        let info = mock_info(CREATOR, &[]);
+        let init_msg = InstantiateMsg {
+            quorum: Some(QuorumConfig { threshold: 2 }),
+            ..default_init()
+        };
+        // revealed outputs carry no attestation to verify
+        contract
+            .instantiate(
+                deps.as_mut(),
+                mock_env(),
+                info.clone(),
+                InstantiateMsg {
+                    verifier: Some(VerifierConfig::Oracle {}),
+                    ..init_msg.clone()
+                },
+            )
+            .unwrap_err();
+        contract
+            .instantiate(deps.as_mut(), mock_env(), info.clone(), init_msg)
+            .unwrap();
+        for oracle in ["aaa", "bbb", "ccc"] {
+            register_oracle(&contract, deps.as_mut(), oracle, None);
+        }
+
+        let exec_msg = ExecuteMsg::Mint {
+            token_id: "Not used".to_string(),
+            owner: "john".to_string(),
+            token_uri: None,
+            extension: Some(Metadata {
+                description: Some("Hello".into()),
+                ..Metadata::default()
+            }),
+            prompt: "Hello".to_string(),
+            variables: None,
+            callback: None,
+            prompt_id: None,
+        };
+        contract
+            .execute(deps.as_mut(), mock_env(), info, exec_msg)
+            .unwrap();
+
+        // single writer responses are disabled
+        let exec_msg = ExecuteMsg::Response {
+            token_id: "0".to_string(),
+            output: "MEOW".to_string(),
+            attestation: None,
+            generation: None,
+        };
+        contract
+            .execute(deps.as_mut(), mock_env(), mock_info("aaa", &[]), exec_msg)
+            .unwrap_err();
+        let exec_msg = ExecuteMsg::FailRequest {
+            token_id: "0".to_string(),
+            reason: "busy".to_string(),
+        };
+        contract
+            .execute(deps.as_mut(), mock_env(), mock_info("aaa", &[]), exec_msg)
+            .unwrap_err();
+
+        let submit = |deps: DepsMut, oracle: &str, output: &str| {
+            let exec_msg = ExecuteMsg::SubmitOutputHash {
+                token_id: "0".to_string(),
+                output_hash: Sha256::digest(output.as_bytes()).to_vec().into(),
+            };
+            contract.execute(deps, mock_env(), mock_info(oracle, &[]), exec_msg)
+        };
+        let reveal = |deps: DepsMut, oracle: &str, output: &str, generation| {
+            let exec_msg = ExecuteMsg::RevealOutput {
+                token_id: "0".to_string(),
+                output: output.to_string(),
+                generation,
+            };
+            contract.execute(deps, mock_env(), mock_info(oracle, &[]), exec_msg)
+        };
+
+        submit(deps.as_mut(), "aaa", "MEOW").unwrap();
+        submit(deps.as_mut(), "aaa", "MEOW").unwrap_err();
+        submit(deps.as_mut(), "bbb", "WOOF").unwrap();
+        reveal(deps.as_mut(), "aaa", "MEOW", None).unwrap_err();
+        submit(deps.as_mut(), "ccc", "MEOW").unwrap();
+
+        reveal(deps.as_mut(), "bbb", "WOOF", None).unwrap_err();
+        reveal(deps.as_mut(), "ccc", "WOOF", None).unwrap_err();
+
+        // revealed outputs go through the same generation checks as responses
+        let weights_hash = cosmwasm_std::HexBinary::from(&[7u8; 32]);
+        let exec_msg = ExecuteMsg::AllowModel {
+            model_id: "llama3".to_string(),
+            weights_hash: weights_hash.clone(),
+        };
+        contract
+            .execute(deps.as_mut(), mock_env(), mock_info(CREATOR, &[]), exec_msg)
+            .unwrap();
+        reveal(deps.as_mut(), "ccc", "MEOW", None).unwrap_err();
+        let query_msg: QueryMsg<Empty> = QueryMsg::Request {
+            token_id: "0".to_string(),
+        };
+        let request: Request = from_json(
+            contract
+                .query(deps.as_ref(), mock_env(), query_msg)
+                .unwrap(),
+        )
+        .unwrap();
+        let generation = Generation {
+            model_id: "llama3".to_string(),
+            weights_hash,
+            params: InferenceParams {
+                temperature: cosmwasm_std::Decimal::percent(70),
+                top_p: cosmwasm_std::Decimal::percent(90),
+                max_tokens: 256,
+                seed: Some(request.seed),
+            },
+            oracle_version: "1.2.0".to_string(),
+        };
+        reveal(deps.as_mut(), "ccc", "MEOW", Some(generation)).unwrap();
+
+        let query_msg: QueryMsg<Empty> = QueryMsg::NftInfo {
+            token_id: "0".to_string(),
+        };
+        let res: NftInfoResponse<Metadata> = from_json(
+            contract
+                .query(deps.as_ref(), mock_env(), query_msg)
+                .unwrap(),
+        )
+        .unwrap();
+        assert_eq!(res.extension.image, Some("MEOW".to_string()));
+
+        for (oracle, agreed, disagreed) in [("aaa", 1, 0), ("bbb", 0, 1)] {
+            let query_msg: QueryMsg<Empty> = QueryMsg::Reputation {
+                oracle: oracle.to_string(),
+            };
+            let res: Reputation = from_json(
+                contract
+                    .query(deps.as_ref(), mock_env(), query_msg)
+                    .unwrap(),
+            )
+            .unwrap();
+            assert_eq!((res.agreed, res.disagreed), (agreed, disagreed));
+        }
+    }
+
+    #[test]
+    fn commit_reveal_response() {
+        let mut deps = mock_dependencies();
+        let contract = Cw7007Contract::<Extension, Empty, Empty, Empty>::default();
+
+        let info = mock_info(CREATOR, &[]);
+        let init_msg = InstantiateMsg {
+            reveal_window: Some(cw_utils::Duration::Height(5)),
+            ..default_init()
+        };
+        contract
+            .instantiate(deps.as_mut(), mock_env(), info.clone(), init_msg)
+            .unwrap();
+        for oracle in ["aaa", "bbb"] {
+            register_oracle(&contract, deps.as_mut(), oracle, None);
+        }
+        for _ in 0..2 {
+            let exec_msg = ExecuteMsg::Mint {
+                token_id: "Not used".to_string(),
+                owner: "john".to_string(),
+                token_uri: None,
+                extension: Some(Metadata {
+                    description: Some("Hello".into()),
+                    ..Metadata::default()
+                }),
+                prompt: "Hello".to_string(),
+                variables: None,
+                callback: None,
+                prompt_id: None,
+            };
+            contract
+                .execute(deps.as_mut(), mock_env(), info.clone(), exec_msg)
+                .unwrap();
+        }
+
+        let env_at = |blocks: u64| {
+            let mut env = mock_env();
+            env.block.height += blocks;
+            env
+        };
+        let commit = |deps: DepsMut, token_id: &str| {
+            let exec_msg = ExecuteMsg::CommitResponse {
+                token_id: token_id.to_string(),
+                hash: verifier::commitment_hash(token_id, "MEOW", "pepper", "aaa")
+                    .to_vec()
+                    .into(),
+            };
+            contract.execute(deps, mock_env(), mock_info("aaa", &[]), exec_msg)
+        };
+        let reveal = |deps: DepsMut, env, oracle: &str, token_id: &str, salt: &str| {
+            let exec_msg = ExecuteMsg::RevealResponse {
+                token_id: token_id.to_string(),
+                output: "MEOW".to_string(),
+                salt: salt.to_string(),
+                attestation: None,
+                generation: None,
+            };
+            contract.execute(deps, env, mock_info(oracle, &[]), exec_msg)
+        };
+        let respond = |deps: DepsMut, env, token_id: &str| {
+            let exec_msg = ExecuteMsg::Response {
+                token_id: token_id.to_string(),
+                output: "MEOW".to_string(),
+                attestation: None,
+                generation: None,
+            };
+            contract.execute(deps, env, mock_info("bbb", &[]), exec_msg)
+        };
+
+        commit(deps.as_mut(), "0").unwrap();
+        commit(deps.as_mut(), "0").unwrap_err();
+        // nobody else can respond while the commitment is open
+        respond(deps.as_mut(), env_at(1), "0").unwrap_err();
+
+        reveal(deps.as_mut(), mock_env(), "aaa", "0", "pepper").unwrap_err();
+        reveal(deps.as_mut(), env_at(1), "aaa", "0", "salt").unwrap_err();
+        reveal(deps.as_mut(), env_at(1), "bbb", "0", "pepper").unwrap_err();
+        reveal(deps.as_mut(), env_at(1), "aaa", "0", "pepper").unwrap();
+
+        let query_msg: QueryMsg<Empty> = QueryMsg::NftInfo {
+            token_id: "0".to_string(),
+        };
+        let res: NftInfoResponse<Metadata> = from_json(
+            contract
+                .query(deps.as_ref(), mock_env(), query_msg)
+                .unwrap(),
+        )
+        .unwrap();
+        assert_eq!(res.extension.image, Some("MEOW".to_string()));
+
+        // commitments lapse after the reveal window
+        commit(deps.as_mut(), "1").unwrap();
+        reveal(deps.as_mut(), env_at(6), "aaa", "1", "pepper").unwrap_err();
+        respond(deps.as_mut(), env_at(6), "1").unwrap();
+    }
 }
@@ -0,0 +1,198 @@
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::{CustomMsg, DepsMut, Env, Order, Response, StdError, StdResult, Storage};
+use cw721_base::state::TokenInfo;
+use cw721_base::ContractError;
+use cw_storage_plus::{Bound, Item};
+use semver::Version;
+
+use crate::callback::DEFAULT_CALLBACK_GAS_LIMIT;
+use crate::msg::{MigrateMsg, TokenIdStrategy};
+use crate::state::{AigcData, Cw7007Contract, Extension, Request, RequestStatus};
+use crate::{CONTRACT_NAME, CONTRACT_VERSION};
+
+/// Tokens converted by one migration call unless the message says otherwise
+const DEFAULT_MIGRATE_LIMIT: u32 = 100;
+
+/// The legacy prompt is the first one published, so the one every v0.1 token was made with
+const LEGACY_PROMPT_VERSION: u32 = 1;
+
+/// Collection prompt as stored by v0.1, before prompt versions
+#[cw_serde]
+struct LegacyPromptInfo {
+    prompt: String,
+}
+
+/// Pending token ids as stored by v0.1, before request records
+#[cw_serde]
+struct LegacyRequestIds {
+    ids: Vec<String>,
+}
+
+fn parse_version(version: &str) -> StdResult<Version> {
+    version
+        .parse()
+        .map_err(|_| StdError::generic_err(format!("invalid contract version {}.", version)))
+}
+
+impl<'a, C, E, Q> Cw7007Contract<'a, Extension, C, E, Q>
+where
+    C: CustomMsg,
+    E: CustomMsg,
+    Q: CustomMsg,
+{
+    /// Bring the storage of any older version up to this one, one step at a time
+    pub fn migrate(
+        &self,
+        mut deps: DepsMut,
+        env: Env,
+        msg: MigrateMsg,
+    ) -> Result<Response<C>, ContractError> {
+        let stored = cw2::get_contract_version(deps.storage)?;
+        if stored.contract != CONTRACT_NAME {
+            return Err(ContractError::Std(StdError::generic_err(format!(
+                "cannot migrate from {}.",
+                stored.contract
+            ))));
+        }
+        let from = parse_version(&stored.version)?;
+        let to = parse_version(CONTRACT_VERSION)?;
+        if from > to {
+            return Err(ContractError::Std(StdError::generic_err(format!(
+                "cannot migrate from version {} down to {}.",
+                from, to
+            ))));
+        }
+
+        // every step upgrades the storage of the versions before it, in release order.
+        // A step may take several calls, the stored version moves only once all are done
+        let limit = msg.limit.unwrap_or(DEFAULT_MIGRATE_LIMIT);
+        if limit == 0 {
+            return Err(ContractError::Std(StdError::generic_err(
+                "migration limit must not be zero.",
+            )));
+        }
+        let mut complete = true;
+        if from < Version::new(0, 2, 0) {
+            complete = self.migrate_v0_1(deps.branch(), &env, limit)?;
+        }
+
+        if complete {
+            cw2::set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+        }
+
+        Ok(Response::new()
+            .add_attribute("action", "migrate")
+            .add_attribute("from_version", from.to_string())
+            .add_attribute("to_version", to.to_string())
+            .add_attribute("complete", complete.to_string()))
+    }
+
+    /// v0.1 kept the pending token ids in a single `Vec` and the prompt without versions.
+    /// Every token gets a request and an ERC-7007 record instead, taken from its metadata:
+    /// the user prompt was the description and the output the image.
+    /// Mint times are lost, so records are dated at the migration.
+    ///
+    /// The first call converts the prompt and the pending ids, then every call converts up
+    /// to `limit` more tokens after the stored cursor. Returns whether all tokens are done
+    fn migrate_v0_1(&self, deps: DepsMut, env: &Env, limit: u32) -> Result<bool, ContractError> {
+        let legacy_prompt: Item<LegacyPromptInfo> = Item::new("prompt");
+        let legacy_request_ids: Item<LegacyRequestIds> = Item::new("requestids");
+
+        // a cursor is left by every call but the last, so none means this is the first
+        let start = self.migration_cursor.may_load(deps.storage)?;
+        if start.is_none() {
+            let prompt = legacy_prompt.load(deps.storage)?.prompt;
+            let pending = legacy_request_ids
+                .may_load(deps.storage)?
+                .map(|request_ids| request_ids.ids)
+                .unwrap_or_default();
+            legacy_prompt.remove(deps.storage);
+            legacy_request_ids.remove(deps.storage);
+            self.publish_prompt(deps.storage, &env.block, prompt)?;
+
+            // features added since are off, as in a fresh instantiate
+            self.verifier.save(deps.storage, &None)?;
+            self.optimistic.save(deps.storage, &None)?;
+            self.request_timeout.save(deps.storage, &None)?;
+            self.mint_fee.save(deps.storage, &None)?;
+            self.staking.save(deps.storage, &None)?;
+            self.assignment.save(deps.storage, &None)?;
+            self.quorum.save(deps.storage, &None)?;
+            self.reveal_window.save(deps.storage, &None)?;
+            self.token_id_strategy
+                .save(deps.storage, &TokenIdStrategy::Counter {})?;
+            self.prompt_template.save(deps.storage, &None)?;
+            self.callback_gas_limit
+                .save(deps.storage, &DEFAULT_CALLBACK_GAS_LIMIT)?;
+
+            // pending tokens keep their place in the queue right away, the batches skip them
+            for token_id in pending {
+                if let Some(token) = self.cw721.tokens.may_load(deps.storage, &token_id)? {
+                    self.migrate_v0_1_token(deps.storage, env, token_id, token, false)?;
+                }
+            }
+            // v0.1 ids were counted from the number of tokens, so never reuse one.
+            // Burnt tokens may leave higher ids, the batches raise the counter past them
+            let count = self.cw721.token_count(deps.storage)?;
+            self.token_id_counter.save(deps.storage, &count)?;
+        }
+
+        let tokens = self
+            .cw721
+            .tokens
+            .range(
+                deps.storage,
+                start.as_deref().map(Bound::exclusive),
+                None,
+                Order::Ascending,
+            )
+            .take(limit as usize)
+            .collect::<StdResult<Vec<(String, TokenInfo<Extension>)>>>()?;
+        let complete = tokens.len() < limit as usize;
+
+        let mut next_id = self.token_id_counter.load(deps.storage)?;
+        let mut last = None;
+        for (token_id, token) in tokens {
+            if let Ok(id) = token_id.parse::<u64>() {
+                next_id = next_id.max(id + 1);
+            }
+            if !self.requests.has(deps.storage, &token_id) {
+                self.migrate_v0_1_token(deps.storage, env, token_id.clone(), token, true)?;
+            }
+            last = Some(token_id);
+        }
+        self.token_id_counter.save(deps.storage, &next_id)?;
+
+        match last {
+            Some(token_id) if !complete => self.migration_cursor.save(deps.storage, &token_id)?,
+            _ => self.migration_cursor.remove(deps.storage),
+        }
+        Ok(complete)
+    }
+
+    /// Request and ERC-7007 record of one v0.1 token, answered unless still pending
+    fn migrate_v0_1_token(
+        &self,
+        storage: &mut dyn Storage,
+        env: &Env,
+        token_id: String,
+        token: TokenInfo<Extension>,
+        fulfilled: bool,
+    ) -> Result<(), ContractError> {
+        let metadata = token.extension.unwrap_or_default();
+        let mut record = AigcData::new(
+            metadata.description.unwrap_or_default(),
+            LEGACY_PROMPT_VERSION,
+            &env.block,
+        );
+        let mut request = Request::new(token_id.clone(), token.owner, env);
+        if fulfilled {
+            record.aigc_data = metadata.image;
+            record.fulfilled_at = Some(env.block.time);
+            request.transition(RequestStatus::Fulfilled, &env.block)?;
+        }
+        self.aigc_data.save(storage, &token_id, &record)?;
+        self.requests.save(storage, &token_id, &request)?;
+        Ok(())
+    }
+}
@@ -0,0 +1,168 @@
+use cosmwasm_std::{
+    CustomMsg, Decimal, DepsMut, Env, HexBinary, MessageInfo, Order, Response, StdError, StdResult,
+    Storage,
+};
+use cw721_base::ContractError;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+
+use crate::msg::Generation;
+use crate::state::{Cw7007Contract, Trait};
+
+/// Highest sampling temperature accepted
+const MAX_TEMPERATURE: u64 = 2;
+
+/// Seed of a new request, derived from the chain so that anyone can recompute it.
+/// Pseudo-random assignment draws from it too, predictable by block producers
+/// but good enough to spread load between oracles
+pub fn generation_seed(env: &Env, token_id: &str) -> u64 {
+    let mut hasher = Sha256::new();
+    hasher.update(env.block.height.to_be_bytes());
+    hasher.update(env.block.time.nanos().to_be_bytes());
+    hasher.update(
+        env.transaction
+            .as_ref()
+            .map_or(u32::MAX, |tx| tx.index)
+            .to_be_bytes(),
+    );
+    for field in [env.contract.address.as_str(), token_id] {
+        hasher.update((field.len() as u64).to_be_bytes());
+        hasher.update(field.as_bytes());
+    }
+    let hash: [u8; 32] = hasher.finalize().into();
+    u64::from_be_bytes(hash[..8].try_into().unwrap())
+}
+
+/// Generation details as metadata traits, for marketplaces to display
+pub fn generation_traits(generation: &Generation) -> Vec<Trait> {
+    let params = &generation.params;
+    let mut traits = vec![
+        ("model", generation.model_id.clone()),
+        ("weights_hash", generation.weights_hash.to_hex()),
+        ("temperature", params.temperature.to_string()),
+        ("top_p", params.top_p.to_string()),
+        ("max_tokens", params.max_tokens.to_string()),
+    ];
+    if let Some(seed) = params.seed {
+        traits.push(("seed", seed.to_string()));
+    }
+    traits.push(("oracle_version", generation.oracle_version.clone()));
+
+    traits
+        .into_iter()
+        .map(|(trait_type, value)| Trait {
+            display_type: None,
+            trait_type: trait_type.to_string(),
+            value,
+        })
+        .collect()
+}
+
+impl<'a, T, C, E, Q> Cw7007Contract<'a, T, C, E, Q>
+where
+    T: Serialize + DeserializeOwned + Clone,
+    C: CustomMsg,
+    E: CustomMsg,
+    Q: CustomMsg,
+{
+    pub fn allow_model(
+        &self,
+        deps: DepsMut,
+        info: MessageInfo,
+        model_id: String,
+        weights_hash: HexBinary,
+    ) -> Result<Response<C>, ContractError> {
+        cw_ownable::assert_owner(deps.storage, &info.sender)?;
+
+        if model_id.is_empty() || weights_hash.is_empty() {
+            return Err(ContractError::Std(StdError::generic_err(
+                "model id and weights hash must not be empty.",
+            )));
+        }
+        self.models.save(deps.storage, &model_id, &weights_hash)?;
+
+        Ok(Response::new()
+            .add_attribute("action", "allow_model")
+            .add_attribute("sender", info.sender)
+            .add_attribute("model_id", model_id)
+            .add_attribute("weights_hash", weights_hash.to_hex()))
+    }
+
+    pub fn disallow_model(
+        &self,
+        deps: DepsMut,
+        info: MessageInfo,
+        model_id: String,
+    ) -> Result<Response<C>, ContractError> {
+        cw_ownable::assert_owner(deps.storage, &info.sender)?;
+
+        if !self.models.has(deps.storage, &model_id) {
+            return Err(ContractError::Std(StdError::generic_err(
+                "model is not allowed.",
+            )));
+        }
+        self.models.remove(deps.storage, &model_id);
+
+        Ok(Response::new()
+            .add_attribute("action", "disallow_model")
+            .add_attribute("sender", info.sender)
+            .add_attribute("model_id", model_id))
+    }
+
+    /// Make sure a generation uses an allowed model with sane parameters and the request seed.
+    /// Generations are only optional as long as no model is allowed
+    pub fn check_generation(
+        &self,
+        storage: &dyn Storage,
+        token_id: &str,
+        generation: Option<&Generation>,
+    ) -> StdResult<()> {
+        let generation = match generation {
+            Some(generation) => generation,
+            None if self
+                .models
+                .keys(storage, None, None, Order::Ascending)
+                .next()
+                .is_none() =>
+            {
+                return Ok(())
+            }
+            None => return Err(StdError::generic_err("generation is required.")),
+        };
+        // checked first, whatever models are allowed, so that honest outputs stay reproducible
+        if generation.params.seed != Some(self.requests.load(storage, token_id)?.seed) {
+            return Err(StdError::generic_err(
+                "generation must use the request seed.",
+            ));
+        }
+
+        match self.models.may_load(storage, &generation.model_id)? {
+            Some(weights_hash) if weights_hash == generation.weights_hash => {}
+            Some(_) => {
+                return Err(StdError::generic_err(
+                    "weights hash does not match the model.",
+                ))
+            }
+            None => return Err(StdError::generic_err("model is not allowed.")),
+        }
+
+        let params = &generation.params;
+        if params.temperature > Decimal::from_ratio(MAX_TEMPERATURE, 1u64) {
+            return Err(StdError::generic_err(format!(
+                "temperature must not exceed {}.",
+                MAX_TEMPERATURE
+            )));
+        }
+        if params.top_p.is_zero() || params.top_p > Decimal::one() {
+            return Err(StdError::generic_err("top_p must be in (0, 1]."));
+        }
+        if params.max_tokens == 0 {
+            return Err(StdError::generic_err("max tokens must not be zero."));
+        }
+        if generation.oracle_version.is_empty() {
+            return Err(StdError::generic_err("oracle version must not be empty."));
+        }
+        Ok(())
+    }
+}
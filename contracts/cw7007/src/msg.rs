@@ -1,8 +1,13 @@
 use cosmwasm_schema::{cw_serde, QueryResponses};
-use cosmwasm_std::Binary;
+use cosmwasm_std::{
+    to_json_binary, Addr, Binary, Coin, CosmosMsg, Decimal, HexBinary, StdResult, Timestamp,
+    Uint128, WasmMsg,
+};
+use cw20::Cw20ReceiveMsg;
 use cw721::Expiration;
 use cw721_base::MinterResponse;
 use cw_ownable::{cw_ownable_execute, cw_ownable_query};
+use cw_utils::Duration;
 use schemars::JsonSchema;
 
 #[cw_serde]
@@ -19,11 +24,372 @@ pub struct InstantiateMsg {
 
     /// Prompt of this Cw7007
     pub prompt: String,
+
+    /// Backend used to check `Verify` proofs. Verification is disabled if unset
+    pub verifier: Option<VerifierConfig>,
+
+    /// Enables optimistic responses, which can be challenged before they finalize
+    pub optimistic: Option<OptimisticConfig>,
+
+    /// How long a request stays pending before anyone can expire it. Never expires if unset
+    pub request_timeout: Option<Duration>,
+
+    /// Price of a mint. Minting is free if unset
+    pub mint_fee: Option<MintFeeConfig>,
+
+    /// Requires oracles to bond stake before responding
+    pub staking: Option<StakingConfig>,
+
+    /// Assigns every request to a single oracle. Any active oracle can respond if unset
+    pub assignment: Option<AssignmentConfig>,
+
+    /// Finalizes outputs only once enough oracles agree on them, instead of `Response`
+    pub quorum: Option<QuorumConfig>,
+
+    /// How long a `CommitResponse` stays valid. Commit-reveal is disabled if unset
+    pub reveal_window: Option<Duration>,
+
+    /// How the id of a minted token is chosen. Sequential if unset
+    pub token_id_strategy: Option<TokenIdStrategy>,
+
+    /// Renders the prompt of every mint from the given variables.
+    /// Minters write the prompt themselves if unset
+    pub prompt_template: Option<PromptTemplate>,
+
+    /// Gas a request callback can use, so that it can't exhaust the gas of the response.
+    /// Defaults to 500000
+    pub callback_gas_limit: Option<u64>,
+}
+
+#[cw_serde]
+pub struct MigrateMsg {
+    /// Most tokens converted by this call, the rest by the next ones. Defaults to 100
+    pub limit: Option<u32>,
+}
+
+/// Prompt with `{name}` placeholders, filled in at mint. `{{` and `}}` stand for literal braces
+#[cw_serde]
+pub struct PromptTemplate {
+    pub template: String,
+    /// One entry per placeholder of the template
+    pub variables: Vec<TemplateVariable>,
+}
+
+/// Constraints on the value of a placeholder
+#[cw_serde]
+pub struct TemplateVariable {
+    /// Placeholder name: lowercase ascii letters, digits and `_`
+    pub name: String,
+    /// Maximum number of characters of the value
+    pub max_length: u32,
+    /// Only these values are accepted if set
+    pub choices: Option<Vec<String>>,
+    /// Characters the value is made of. Any character if unset
+    pub charset: Option<Charset>,
+}
+
+#[cw_serde]
+pub enum Charset {
+    /// Ascii letters and spaces
+    Alphabetic {},
+    /// Ascii digits
+    Numeric {},
+    /// Ascii letters, digits and spaces
+    Alphanumeric {},
+    /// Printable ascii characters, spaces included
+    Printable {},
+}
+
+/// Value of a template placeholder given at mint
+#[cw_serde]
+pub struct PromptVariable {
+    pub name: String,
+    pub value: String,
+}
+
+/// How the id of a minted token is chosen
+#[cw_serde]
+pub enum TokenIdStrategy {
+    /// Sequential number, never reused even after a burn
+    Counter {},
+    /// The `token_id` given at mint. Up to 64 ASCII letters, digits, `-`, `_` or `.`
+    Caller {},
+    /// Hex encoded sha256 of the prompt, as in ERC-7007. A prompt can only be minted once
+    PromptHash {},
+}
+
+#[cw_serde]
+pub struct QuorumConfig {
+    /// Number of oracles that have to submit the same output hash
+    pub threshold: u32,
+}
+
+#[cw_serde]
+pub struct AssignmentConfig {
+    pub strategy: AssignmentStrategy,
+    /// How long the assigned oracle has to respond before the request can be reassigned
+    pub timeout: Duration,
+}
+
+/// How the oracle of a new request is picked among active oracles
+#[cw_serde]
+pub enum AssignmentStrategy {
+    /// Every oracle in turn
+    RoundRobin {},
+    /// Pseudo-randomly, in proportion to bonded stake. Requires staking
+    StakeWeighted {},
+    /// Pseudo-randomly, seeded from the block
+    Random {},
+}
+
+#[cw_serde]
+pub struct StakingConfig {
+    /// Denom oracles bond in
+    pub denom: String,
+    /// Bonded stake an oracle needs to respond
+    pub min_stake: Uint128,
+    /// Delay between unbonding and claiming, during which the stake can still be slashed
+    pub unbonding_period: Duration,
+    /// Part of the proposer stake slashed when a challenge is upheld, paid to the challenger
+    pub dispute_slash: Decimal,
+}
+
+#[cw_serde]
+pub struct MintFeeConfig {
+    /// Exact funds to send along `Mint`, held in escrow until the request closes
+    pub price: Vec<Coin>,
+    /// Receives `treasury_share` of every fee released to an oracle
+    pub treasury: Option<String>,
+    /// Part of the fee going to the treasury, the rest goes to the fulfilling oracle
+    pub treasury_share: Decimal,
+}
+
+/// Mint arguments, as sent through the CW20 `Receive` hook
+#[cw_serde]
+pub struct MintMsg<T> {
+    /// Only used with the `Caller` token id strategy
+    pub token_id: Option<String>,
+    pub owner: String,
+    pub token_uri: Option<String>,
+    pub extension: T,
+    pub prompt: String,
+    pub variables: Option<Vec<PromptVariable>>,
+    pub callback: Option<Callback>,
+    /// Registered prompt to mint under, the collection prompt if unset
+    pub prompt_id: Option<String>,
+}
+
+#[cw_serde]
+pub struct Callback {
+    /// Contract executed with `Cw7007ReceiveMsg` once the output is final
+    pub contract: String,
+    /// Passed through untouched
+    pub payload: Binary,
+}
+
+/// Sent to the callback contract of a request once its output is final.
+/// A failing callback does not revert the response
+#[cw_serde]
+pub struct Cw7007ReceiveMsg {
+    pub token_id: String,
+    pub output: String,
+    pub payload: Binary,
+}
+
+impl Cw7007ReceiveMsg {
+    /// Serialize the message as `{"cw7007_receive": {...}}`
+    pub fn into_binary(self) -> StdResult<Binary> {
+        to_json_binary(&ReceiverExecuteMsg::Cw7007Receive(self))
+    }
+
+    pub fn into_cosmos_msg<C>(self, contract: impl Into<String>) -> StdResult<CosmosMsg<C>> {
+        Ok(WasmMsg::Execute {
+            contract_addr: contract.into(),
+            msg: self.into_binary()?,
+            funds: vec![],
+        }
+        .into())
+    }
+}
+
+/// Execute message a callback contract has to accept
+#[cw_serde]
+pub enum ReceiverExecuteMsg {
+    Cw7007Receive(Cw7007ReceiveMsg),
+}
+
+/// Sent to every registered hook contract. A failing hook reverts the whole transaction
+#[cw_serde]
+pub enum Cw7007HookMsg {
+    /// A request was minted
+    Mint {
+        token_id: String,
+        requester: String,
+        owner: String,
+    },
+    /// The output of a request is final
+    Response {
+        token_id: String,
+        oracle: String,
+        output: String,
+    },
+    /// A token was verified
+    Verify { token_id: String, verifier: String },
+    /// A token was burned
+    Burn { token_id: String, sender: String },
+}
+
+impl Cw7007HookMsg {
+    /// Serialize the message as `{"cw7007_hook": {...}}`
+    pub fn into_binary(self) -> StdResult<Binary> {
+        to_json_binary(&HookExecuteMsg::Cw7007Hook(self))
+    }
+
+    pub fn into_cosmos_msg<C>(self, contract: impl Into<String>) -> StdResult<CosmosMsg<C>> {
+        Ok(WasmMsg::Execute {
+            contract_addr: contract.into(),
+            msg: self.into_binary()?,
+            funds: vec![],
+        }
+        .into())
+    }
+}
+
+/// Execute message a hook contract has to accept
+#[cw_serde]
+pub enum HookExecuteMsg {
+    Cw7007Hook(Cw7007HookMsg),
+}
+
+/// Messages accepted in the `msg` of a CW20 `Send` to this contract
+#[cw_serde]
+pub enum ReceiveMsg<T> {
+    /// Mint a request paid with the sent tokens. The sender of the tokens is the requester
+    Mint(MintMsg<T>),
+}
+
+#[cw_serde]
+pub struct OptimisticConfig {
+    /// Number of blocks during which a proposed output can be challenged
+    pub challenge_window: u64,
+    /// Bond a challenger has to post, slashed if the challenge is rejected
+    pub challenge_bond: Coin,
+    /// Resolves disputes. The contract owner if unset
+    pub arbiter: Option<String>,
+}
+
+/// Proof verification backends
+#[cw_serde]
+pub enum VerifierConfig {
+    /// The proof is forwarded to an external contract answering `VerifierQueryMsg`
+    Contract { address: String },
+    /// The proof is a JSON encoded `Attestation` by a registered oracle key.
+    /// In this mode `Response` is only accepted with a valid attestation
+    Oracle {},
+    /// The proof is a base64 encoded, compressed BN254 Groth16 proof.
+    /// Public inputs are the hashes of (prompt, input, seed) and of output,
+    /// checked against the verifying key uploaded by the owner
+    Groth16 {},
+}
+
+#[cw_serde]
+pub enum SignatureScheme {
+    Secp256k1,
+    Ed25519,
+}
+
+#[cw_serde]
+pub struct OracleKey {
+    pub scheme: SignatureScheme,
+    pub pubkey: Binary,
+}
+
+/// Public description of an oracle
+#[cw_serde]
+pub struct OracleMetadata {
+    pub name: String,
+    /// Where and how the oracle serves requests
+    pub endpoint: String,
+    /// Key its attestations are signed with
+    pub key: Option<OracleKey>,
+    /// Models the oracle can run
+    pub models: Vec<String>,
+}
+
+/// Oracle signature over the canonical hash of (prompt, input, seed, output)
+#[cw_serde]
+pub struct Attestation {
+    /// Signing oracle, which must be the one submitting the output
+    pub oracle: String,
+    pub signature: Binary,
+}
+
+/// How an output was generated, as reported by the oracle
+#[cw_serde]
+pub struct Generation {
+    /// Id of a model allowed by the owner
+    pub model_id: String,
+    /// Hash of the model weights, must match the allowed model
+    pub weights_hash: HexBinary,
+    pub params: InferenceParams,
+    /// Version of the oracle software
+    pub oracle_version: String,
+}
+
+#[cw_serde]
+pub struct InferenceParams {
+    pub temperature: Decimal,
+    pub top_p: Decimal,
+    pub max_tokens: u32,
+    pub seed: Option<u64>,
+}
+
+#[cw_serde]
+pub struct ModelInfo {
+    pub model_id: String,
+    pub weights_hash: HexBinary,
+}
+
+#[cw_serde]
+pub struct ModelsResponse {
+    pub models: Vec<ModelInfo>,
+}
+
+/// Query interface an external verifier contract has to implement
+#[cw_serde]
+#[derive(QueryResponses)]
+pub enum VerifierQueryMsg {
+    #[returns(VerifyProofResponse)]
+    VerifyProof {
+        prompt: String,
+        input: String,
+        /// Seed of the request, the output has to be sampled with it
+        seed: u64,
+        output: String,
+        proof: String,
+    },
+}
+
+#[cw_serde]
+pub struct VerifyProofResponse {
+    pub valid: bool,
 }
 
 #[cw_serde]
 pub struct PromptInfoResponse {
     pub prompt: String,
+    /// Version of the prompt, starting at 1 and bumped by `UpdatePrompt`
+    pub version: u32,
+}
+
+#[cw_serde]
+pub struct RegisteredPromptsResponse {
+    pub prompts: Vec<crate::state::RegisteredPrompt>,
+}
+
+#[cw_serde]
+pub struct PromptVersionsResponse {
+    pub versions: Vec<crate::state::PromptVersion>,
 }
 
 #[cw_serde]
@@ -31,6 +397,69 @@ pub struct RequestIdsResponse {
     pub ids: Vec<String>,
 }
 
+#[cw_serde]
+pub enum VerificationStatus {
+    Unverified,
+    Verified {
+        proof: String,
+        verifier: Addr,
+        block_height: u64,
+        time: Timestamp,
+    },
+}
+
+#[cw_serde]
+pub struct VerificationResponse {
+    pub token_id: String,
+    pub status: VerificationStatus,
+}
+
+#[cw_serde]
+pub struct OracleInfo {
+    pub address: Addr,
+    pub oracle: crate::state::Oracle,
+}
+
+#[cw_serde]
+pub struct OraclesResponse {
+    pub oracles: Vec<OracleInfo>,
+}
+
+#[cw_serde]
+pub struct Vote {
+    pub oracle: Addr,
+    pub output_hash: HexBinary,
+}
+
+#[cw_serde]
+pub struct VotesResponse {
+    pub votes: Vec<Vote>,
+    /// Output hash the quorum agreed on, if reached
+    pub agreed: Option<HexBinary>,
+}
+
+#[cw_serde]
+pub struct CommitmentInfo {
+    pub oracle: Addr,
+    pub commitment: crate::state::Commitment,
+}
+
+#[cw_serde]
+pub struct CommitmentsResponse {
+    pub commitments: Vec<CommitmentInfo>,
+}
+
+#[cw_serde]
+pub struct Cw20PriceInfo {
+    pub token: Addr,
+    pub price: Uint128,
+}
+
+#[cw_serde]
+pub struct Cw20PricesResponse {
+    pub prices: Vec<Cw20PriceInfo>,
+}
+
 #[cw_ownable_query]
 #[cw_serde]
 #[derive(QueryResponses)]
@@ -122,9 +551,160 @@ pub enum QueryMsg<Q: JsonSchema> {
     #[returns(PromptInfoResponse)]
     Prompt {},
 
-    /// Return Requests
+    /// List the models oracles are allowed to generate with
+    #[returns(ModelsResponse)]
+    Models {
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+
+    /// Return the model and parameters the output of a token was generated with
+    #[returns(Option<Generation>)]
+    Generation { token_id: String },
+
+    /// Return one version of the collection prompt
+    #[returns(crate::state::PromptVersion)]
+    PromptVersion { version: u32 },
+
+    /// List the versions of the collection prompt, oldest first
+    #[returns(PromptVersionsResponse)]
+    PromptVersions {
+        start_after: Option<u32>,
+        limit: Option<u32>,
+    },
+
+    /// Return a prompt of the registry
+    #[returns(crate::state::RegisteredPrompt)]
+    RegisteredPrompt { prompt_id: String },
+
+    /// List the prompts of the registry, by id
+    #[returns(RegisteredPromptsResponse)]
+    RegisteredPrompts {
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+
+    /// Lists the token_ids minted under a registered prompt
+    #[returns(cw721::TokensResponse)]
+    TokensByPrompt {
+        prompt_id: String,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+
+    /// Return the template mint prompts are rendered from
+    #[returns(Option<PromptTemplate>)]
+    PromptTemplate {},
+
+    /// With Enumerable extension.
+    /// Requires pagination. Lists the token_ids of pending requests.
+    #[returns(RequestIdsResponse)]
+    RequestIds {
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+
+    /// Return the full request record of a token
+    #[returns(crate::state::Request)]
+    Request { token_id: String },
+
+    /// Return the prompt of a token and the content generated for it
+    #[returns(crate::state::AigcData)]
+    AigcData { token_id: String },
+
+    /// Return how long requests stay pending
+    #[returns(Option<Duration>)]
+    RequestTimeout {},
+
+    /// Return how the id of a minted token is chosen
+    #[returns(TokenIdStrategy)]
+    TokenIdStrategy {},
+
+    /// Return the mint fee configuration
+    #[returns(Option<MintFeeConfig>)]
+    MintFee {},
+
+    /// Return the configured verifier backend
+    #[returns(Option<VerifierConfig>)]
+    Verifier {},
+
+    /// Return the verification status of a token
+    #[returns(VerificationResponse)]
+    Verification { token_id: String },
+
+    /// Return the optimistic mode configuration
+    #[returns(Option<OptimisticConfig>)]
+    Optimistic {},
+
+    /// Return the output proposed for a token in optimistic mode
+    #[returns(Option<crate::state::Proposal>)]
+    Proposal { token_id: String },
+
+    /// Return the Groth16 verifying key
+    #[returns(Option<Binary>)]
+    VerifyingKey {},
+
+    /// Return the staking configuration
+    #[returns(Option<StakingConfig>)]
+    Staking {},
+
+    /// Return the stake of an oracle
+    #[returns(crate::state::Stake)]
+    Stake { oracle: String },
+
+    /// Return the assignment configuration
+    #[returns(Option<AssignmentConfig>)]
+    Assignment {},
+
+    /// List pending requests assigned to an oracle
     #[returns(RequestIdsResponse)]
-    RequestIds {},
+    AssignedRequests {
+        oracle: String,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+
+    /// Return the quorum configuration
+    #[returns(Option<QuorumConfig>)]
+    Quorum {},
+
+    /// List the output hashes submitted for a token in quorum mode
+    #[returns(VotesResponse)]
+    Votes { token_id: String },
+
+    /// Return how often an oracle agreed with the quorum
+    #[returns(crate::state::Reputation)]
+    Reputation { oracle: String },
+
+    /// Return how long commitments stay valid
+    #[returns(Option<Duration>)]
+    RevealWindow {},
+
+    /// List the commitments posted for a token
+    #[returns(CommitmentsResponse)]
+    Commitments { token_id: String },
+
+    /// Return a registered oracle
+    #[returns(Option<crate::state::Oracle>)]
+    Oracle { address: String },
+
+    /// List registered oracles
+    #[returns(OraclesResponse)]
+    Oracles {
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+
+    /// List CW20 tokens accepted as payment, with their mint price
+    #[returns(Cw20PricesResponse)]
+    Cw20Prices {
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+
+    /// List the contracts notified of mints, responses, verifications and burns
+    #[returns(cw_controllers::HooksResponse)]
+    Hooks {},
 }
 
 #[cw_ownable_execute]
@@ -159,7 +739,7 @@ pub enum ExecuteMsg<T, E> {
 
     /// Mint a new NFT, can only be called by the contract minter
     Mint {
-        /// Unique ID of the NFT
+        /// Unique ID of the NFT. Ignored unless the token id strategy is `Caller`
         token_id: String,
         /// The owner of the newly minter NFT
         owner: String,
@@ -169,17 +749,177 @@ pub enum ExecuteMsg<T, E> {
         token_uri: Option<String>,
         /// Any custom extension used by this contract
         extension: T,
+        /// Prompt the oracles generate the token's content from.
+        /// Must be empty when the collection has a prompt template
+        prompt: String,
+        /// Values of the prompt template placeholders
+        variables: Option<Vec<PromptVariable>>,
+        /// Contract notified with a `Cw7007ReceiveMsg` once the output is final
+        callback: Option<Callback>,
+        /// Registered prompt to mint under, the collection prompt if unset
+        prompt_id: Option<String>,
     },
 
-    /// Burn an NFT the sender has access to
+    /// Burn an NFT the sender has access to. A pending request is cancelled
+    /// and its escrow refunded to the requester
     Burn { token_id: String },
 
     /// Extension msg
     Extension { msg: E },
 
-    /// Update image from response(output)
-    Response { token_id: String, output: String },
+    /// Update image from response(output), can only be called by an active oracle
+    Response {
+        token_id: String,
+        output: String,
+        /// Oracle signature, required when the verifier is `Oracle`
+        attestation: Option<Attestation>,
+        /// Model and parameters the output was generated with, required once models are allowed
+        generation: Option<Generation>,
+    },
 
-    /// Verify response
+    /// Verify response, can only be called by an active oracle
     Verify { token_id: String, proof: String },
+
+    /// Register an oracle, or update its metadata if already registered.
+    /// Can only be called by the contract owner
+    RegisterOracle {
+        oracle: String,
+        metadata: OracleMetadata,
+    },
+
+    /// Unregister an oracle, can only be called by the contract owner.
+    /// A removed oracle can't register again by bonding, only through `RegisterOracle`
+    RemoveOracle { oracle: String },
+
+    /// Stop an oracle from serving requests, can only be called by the contract owner
+    PauseOracle { oracle: String },
+
+    /// Let a paused oracle serve requests again, can only be called by the contract owner
+    ResumeOracle { oracle: String },
+
+    /// Bond the sent funds, registering the sender as an oracle on first bond.
+    /// `metadata` is required to register, and updates the sender's metadata otherwise
+    Bond { metadata: Option<OracleMetadata> },
+
+    /// Start unbonding part of the sender's stake
+    Unbond { amount: Uint128 },
+
+    /// Withdraw stake whose unbonding period is over
+    Claim {},
+
+    /// Burn part of an oracle's stake for a wrong or missing output,
+    /// can only be called by the contract owner
+    Slash { oracle: String, amount: Uint128 },
+
+    /// Commit to an output without disclosing it, can only be called by an active oracle.
+    /// `hash` is the sha256 of "cw7007" followed by token_id, output, salt and the sender
+    /// address, each prefixed with its byte length as a big endian u64
+    CommitResponse { token_id: String, hash: HexBinary },
+
+    /// Disclose a committed output before the reveal window is over.
+    /// The output is then handled like a `Response`
+    RevealResponse {
+        token_id: String,
+        output: String,
+        salt: String,
+        /// Oracle signature, required when the verifier is `Oracle`
+        attestation: Option<Attestation>,
+        /// Model and parameters the output was generated with, required once models are allowed
+        generation: Option<Generation>,
+    },
+
+    /// Submit the sha256 hash of the output of a token in quorum mode.
+    /// Can only be called by an active oracle, once per token
+    SubmitOutputHash {
+        token_id: String,
+        output_hash: HexBinary,
+    },
+
+    /// Write the output the quorum agreed on into the token.
+    /// Can only be called by an oracle that voted for it
+    RevealOutput {
+        token_id: String,
+        output: String,
+        generation: Option<Generation>,
+    },
+
+    /// Hand a pending request over to another oracle once its assignee timed out.
+    /// Can be called by anyone
+    ReassignRequest { token_id: String },
+
+    /// Give up on a pending request, can only be called by an active oracle,
+    /// the assignee if any. Not available in quorum mode
+    FailRequest { token_id: String, reason: String },
+
+    /// Withdraw a pending request past its deadline or its assignee timeout, refunding
+    /// the requester. Can only be called by the requester
+    CancelRequest { token_id: String },
+
+    /// Expire a pending request past its deadline, refunding the requester.
+    /// Can be called by anyone
+    ExpireRequest { token_id: String },
+
+    /// Allow oracles to generate with a model, or update the hash of its weights.
+    /// Can only be called by the contract owner
+    AllowModel {
+        model_id: String,
+        weights_hash: HexBinary,
+    },
+    /// Stop accepting generations from a model. Can only be called by the contract owner
+    DisallowModel { model_id: String },
+
+    /// Publish a new version of the collection prompt, used by the next mints.
+    /// Tokens keep the version they were minted under. Can only be called by the contract owner
+    UpdatePrompt { prompt: String },
+
+    /// Add a prompt to the registry, minted under its own id next to the collection prompt.
+    /// A registered prompt cannot be changed, so that tokens keep the prompt they were minted for.
+    /// Can only be called by the contract owner
+    AddPrompt {
+        prompt_id: String,
+        name: String,
+        prompt: String,
+        /// Exact native funds to send along a mint of this prompt, the collection price if unset
+        price: Option<Vec<Coin>>,
+    },
+    /// Stop minting a registered prompt, can only be called by the contract owner
+    ClosePrompt { prompt_id: String },
+
+    /// Replace the prompt template, or let minters write their prompt if unset.
+    /// Can only be called by the contract owner
+    SetPromptTemplate { template: Option<PromptTemplate> },
+
+    /// Update the mint fee, can only be called by the contract owner.
+    /// Requests already in escrow keep the fee they paid and its treasury cut
+    UpdateMintFee { mint_fee: Option<MintFeeConfig> },
+
+    /// Entry point of CW20 payments, see `ReceiveMsg`
+    Receive(Cw20ReceiveMsg),
+
+    /// Accept a CW20 token as payment at `price` per mint, or stop accepting it if unset.
+    /// Can only be called by the contract owner
+    UpdateCw20Price {
+        token: String,
+        price: Option<Uint128>,
+    },
+
+    /// Challenge a proposed output, sending the challenge bond along
+    ChallengeResponse { token_id: String },
+
+    /// Settle a challenge, can only be called by the arbiter.
+    /// If upheld the proposal is dropped and the bond refunded,
+    /// otherwise the output is finalized and the bond goes to the proposer
+    ResolveChallenge { token_id: String, upheld: bool },
+
+    /// Finalize an unchallenged proposal once its challenge window is over
+    FinalizeResponse { token_id: String },
+
+    /// Upload the compressed Groth16 verifying key, can only be called by the contract owner
+    SetVerifyingKey { vk: Binary },
+
+    /// Notify `addr` of every mint, response, verify and burn through `Cw7007HookMsg`.
+    /// Can only be called by the contract owner
+    AddHook { addr: String },
+    /// Stop notifying `addr`. Can only be called by the contract owner
+    RemoveHook { addr: String },
 }
@@ -0,0 +1,770 @@
+use cosmwasm_std::{coin, coins, Addr, Binary, Decimal, Empty, Event, Uint128};
+use cw_controllers::HooksResponse;
+use cw_multi_test::error::AnyResult;
+use cw_multi_test::{App, ContractWrapper, Executor};
+use cw_utils::Duration;
+
+use crate::entry;
+use crate::msg::{
+    Callback, Cw7007HookMsg, Cw7007ReceiveMsg, ExecuteMsg, InstantiateMsg, MigrateMsg,
+    OptimisticConfig, OracleMetadata, PromptInfoResponse, QueryMsg, RequestIdsResponse,
+    StakingConfig,
+};
+use crate::state::{AigcData, Cw7007Contract, Extension, Metadata, Request, RequestStatus, Stake};
+
+const OWNER: &str = "owner";
+const ORACLE: &str = "oracle";
+const WATCHER: &str = "watcher";
+const JUDGE: &str = "judge";
+const DENOM: &str = "ustake";
+
+type Msg = ExecuteMsg<Extension, Empty>;
+
+fn instantiate(app: &mut App, msg: InstantiateMsg) -> Addr {
+    let code_id = app.store_code(Box::new(
+        ContractWrapper::new(entry::execute, entry::instantiate, entry::query)
+            .with_reply(entry::reply),
+    ));
+    app.instantiate_contract(code_id, Addr::unchecked(OWNER), &msg, &[], "cw7007", None)
+        .unwrap()
+}
+
+fn init_msg() -> InstantiateMsg {
+    InstantiateMsg {
+        name: "Cats".to_string(),
+        symbol: "MEOW".to_string(),
+        minter: OWNER.to_string(),
+        prompt: "You are a cat. Just answer with 'MEOW'.".into(),
+        verifier: None,
+        optimistic: None,
+        request_timeout: None,
+        mint_fee: None,
+        staking: None,
+        assignment: None,
+        quorum: None,
+        reveal_window: None,
+        token_id_strategy: None,
+        prompt_template: None,
+        callback_gas_limit: None,
+    }
+}
+
+fn setup() -> (App, Addr) {
+    let mut app = App::new(|router, _, storage| {
+        for user in [ORACLE, WATCHER] {
+            router
+                .bank
+                .init_balance(storage, &Addr::unchecked(user), coins(1000, DENOM))
+                .unwrap();
+        }
+    });
+    let contract = instantiate(
+        &mut app,
+        InstantiateMsg {
+            optimistic: Some(OptimisticConfig {
+                challenge_window: 5,
+                challenge_bond: coin(10, DENOM),
+                arbiter: Some(JUDGE.to_string()),
+            }),
+            staking: Some(StakingConfig {
+                denom: DENOM.to_string(),
+                min_stake: Uint128::new(100),
+                unbonding_period: Duration::Height(10),
+                dispute_slash: Decimal::percent(50),
+            }),
+            ..init_msg()
+        },
+    );
+    (app, contract)
+}
+
+/// Callback receiver keeping the last message, failing on a "fail" payload.
+/// A "nested" payload answers token "3" from within the callback, then fails
+mod receiver {
+    use cosmwasm_std::{
+        to_json_binary, to_json_vec, Binary, Deps, DepsMut, Empty, Env, MessageInfo, Response,
+        StdError, StdResult, WasmMsg,
+    };
+
+    use crate::msg::{Cw7007ReceiveMsg, ReceiverExecuteMsg};
+
+    pub fn instantiate(_: DepsMut, _: Env, _: MessageInfo, _: Empty) -> StdResult<Response> {
+        Ok(Response::new())
+    }
+
+    pub fn execute(
+        deps: DepsMut,
+        _: Env,
+        info: MessageInfo,
+        msg: ReceiverExecuteMsg,
+    ) -> StdResult<Response> {
+        let ReceiverExecuteMsg::Cw7007Receive(msg) = msg;
+        if msg.payload.as_slice() == b"fail" {
+            return Err(StdError::generic_err("callback failed"));
+        }
+        if msg.payload.as_slice() == b"nested" {
+            // the second response fails once the first went through
+            let respond = WasmMsg::Execute {
+                contract_addr: info.sender.to_string(),
+                msg: to_json_binary(&super::Msg::Response {
+                    token_id: "3".to_string(),
+                    output: "WOOF".to_string(),
+                    attestation: None,
+                    generation: None,
+                })?,
+                funds: vec![],
+            };
+            return Ok(Response::new().add_messages([respond.clone(), respond]));
+        }
+        deps.storage.set(b"last", &to_json_vec(&msg)?);
+        Ok(Response::new())
+    }
+
+    pub fn query(deps: Deps, _: Env, _: Empty) -> StdResult<Binary> {
+        Ok(deps.storage.get(b"last").unwrap_or_default().into())
+    }
+
+    pub fn last(app: &cw_multi_test::App, addr: &cosmwasm_std::Addr) -> Cw7007ReceiveMsg {
+        app.wrap().query_wasm_smart(addr, &Empty {}).unwrap()
+    }
+}
+
+/// Hook contract keeping every message it was sent
+mod hook {
+    use cosmwasm_std::{
+        from_json, to_json_binary, to_json_vec, Binary, Deps, DepsMut, Empty, Env, MessageInfo,
+        Response, StdResult,
+    };
+
+    use crate::msg::{Cw7007HookMsg, HookExecuteMsg};
+
+    pub fn instantiate(_: DepsMut, _: Env, _: MessageInfo, _: Empty) -> StdResult<Response> {
+        Ok(Response::new())
+    }
+
+    pub fn execute(
+        deps: DepsMut,
+        _: Env,
+        _: MessageInfo,
+        msg: HookExecuteMsg,
+    ) -> StdResult<Response> {
+        let HookExecuteMsg::Cw7007Hook(msg) = msg;
+        let mut received = received(deps.as_ref())?;
+        received.push(msg);
+        deps.storage.set(b"received", &to_json_vec(&received)?);
+        Ok(Response::new())
+    }
+
+    pub fn query(deps: Deps, _: Env, _: Empty) -> StdResult<Binary> {
+        to_json_binary(&received(deps)?)
+    }
+
+    fn received(deps: Deps) -> StdResult<Vec<Cw7007HookMsg>> {
+        match deps.storage.get(b"received") {
+            Some(data) => from_json(data),
+            None => Ok(vec![]),
+        }
+    }
+}
+
+/// v0.1.0 of this contract, writing its storage layout as it was
+mod legacy {
+    use cosmwasm_schema::cw_serde;
+    use cosmwasm_std::{
+        Addr, Binary, Deps, DepsMut, Empty, Env, MessageInfo, Response, StdError, StdResult,
+    };
+    use cw721_base::state::TokenInfo;
+    use cw721_base::{Cw721Contract, InstantiateMsg as Cw721InstantiateMsg};
+    use cw_storage_plus::Item;
+
+    use crate::msg::RequestIdsResponse;
+    use crate::state::{Extension, Metadata};
+
+    #[cw_serde]
+    pub struct InstantiateMsg {
+        pub version: String,
+    }
+
+    #[cw_serde]
+    struct PromptInfo {
+        prompt: String,
+    }
+
+    /// Token "0" was answered, token "1" is still pending
+    pub fn instantiate(
+        mut deps: DepsMut,
+        env: Env,
+        info: MessageInfo,
+        msg: InstantiateMsg,
+    ) -> StdResult<Response> {
+        cw2::set_contract_version(deps.storage, "crates.io:cw7007", msg.version)?;
+        let cw721 = Cw721Contract::<Extension, Empty, Empty, Empty>::default();
+        cw721.instantiate(
+            deps.branch(),
+            env,
+            info,
+            Cw721InstantiateMsg {
+                name: "Cats".to_string(),
+                symbol: "MEOW".to_string(),
+                minter: super::OWNER.to_string(),
+            },
+        )?;
+        for (token_id, image) in [("0", Some("MEOW")), ("1", None)] {
+            let token = TokenInfo {
+                owner: Addr::unchecked("john"),
+                approvals: vec![],
+                token_uri: None,
+                extension: Some(Metadata {
+                    description: Some("Hello".to_string()),
+                    image: image.map(str::to_string),
+                    ..Metadata::default()
+                }),
+            };
+            cw721.tokens.save(deps.storage, token_id, &token)?;
+            cw721.increment_tokens(deps.storage)?;
+        }
+        Item::new("prompt").save(
+            deps.storage,
+            &PromptInfo {
+                prompt: "You are a cat.".to_string(),
+            },
+        )?;
+        Item::new("requestids").save(
+            deps.storage,
+            &RequestIdsResponse {
+                ids: vec!["1".to_string()],
+            },
+        )?;
+        Ok(Response::new())
+    }
+
+    pub fn execute(_: DepsMut, _: Env, _: MessageInfo, _: Empty) -> StdResult<Response> {
+        Ok(Response::new())
+    }
+
+    pub fn query(_: Deps, _: Env, _: Empty) -> StdResult<Binary> {
+        Err(StdError::generic_err("not supported"))
+    }
+}
+
+fn register(app: &mut App, contract: &Addr) {
+    let msg: Msg = ExecuteMsg::RegisterOracle {
+        oracle: ORACLE.to_string(),
+        metadata: OracleMetadata {
+            name: "GPU farm".to_string(),
+            endpoint: "https://gpu.example.com".to_string(),
+            key: None,
+            models: vec![],
+        },
+    };
+    app.execute_contract(Addr::unchecked(OWNER), contract.clone(), &msg, &[])
+        .unwrap();
+}
+
+fn mint(app: &mut App, contract: &Addr) {
+    let msg: Msg = ExecuteMsg::Mint {
+        token_id: "Not used".to_string(),
+        owner: "john".to_string(),
+        token_uri: None,
+        extension: Some(Metadata {
+            description: Some("Hello".into()),
+            ..Metadata::default()
+        }),
+        prompt: "Hello".to_string(),
+        variables: None,
+        callback: None,
+        prompt_id: None,
+    };
+    app.execute_contract(Addr::unchecked("john"), contract.clone(), &msg, &[])
+        .unwrap();
+}
+
+fn respond(app: &mut App, contract: &Addr, token_id: &str) -> AnyResult<()> {
+    let msg: Msg = ExecuteMsg::Response {
+        token_id: token_id.to_string(),
+        output: "WOOF".to_string(),
+        attestation: None,
+        generation: None,
+    };
+    app.execute_contract(Addr::unchecked(ORACLE), contract.clone(), &msg, &[])
+        .map(|_| ())
+}
+
+fn balance(app: &App, user: &str) -> u128 {
+    app.wrap().query_balance(user, DENOM).unwrap().amount.u128()
+}
+
+fn stake(app: &App, contract: &Addr) -> Stake {
+    app.wrap()
+        .query_wasm_smart(
+            contract,
+            &QueryMsg::<Empty>::Stake {
+                oracle: ORACLE.to_string(),
+            },
+        )
+        .unwrap()
+}
+
+#[test]
+fn stake_slash_and_unbond() {
+    let (mut app, contract) = setup();
+    mint(&mut app, &contract);
+    mint(&mut app, &contract);
+
+    // unstaked oracles can't respond
+    respond(&mut app, &contract, "0").unwrap_err();
+
+    let bond = |metadata: Option<OracleMetadata>| -> Msg { ExecuteMsg::Bond { metadata } };
+    app.execute_contract(
+        Addr::unchecked(ORACLE),
+        contract.clone(),
+        &bond(None),
+        &coins(50, DENOM),
+    )
+    .unwrap_err();
+    app.execute_contract(
+        Addr::unchecked(ORACLE),
+        contract.clone(),
+        &bond(Some(OracleMetadata {
+            name: "GPU farm".to_string(),
+            endpoint: "https://gpu.example.com".to_string(),
+            key: None,
+            models: vec!["llama3".to_string()],
+        })),
+        &coins(50, DENOM),
+    )
+    .unwrap();
+    respond(&mut app, &contract, "0").unwrap_err();
+    app.execute_contract(
+        Addr::unchecked(ORACLE),
+        contract.clone(),
+        &bond(None),
+        &coins(150, DENOM),
+    )
+    .unwrap();
+    respond(&mut app, &contract, "0").unwrap();
+
+    // an upheld challenge slashes half of the proposer stake to the challenger
+    let challenge: Msg = ExecuteMsg::ChallengeResponse {
+        token_id: "0".to_string(),
+    };
+    app.execute_contract(
+        Addr::unchecked(WATCHER),
+        contract.clone(),
+        &challenge,
+        &coins(10, DENOM),
+    )
+    .unwrap();
+    let resolve: Msg = ExecuteMsg::ResolveChallenge {
+        token_id: "0".to_string(),
+        upheld: true,
+    };
+    app.execute_contract(Addr::unchecked(JUDGE), contract.clone(), &resolve, &[])
+        .unwrap();
+    assert_eq!(balance(&app, WATCHER), 1100);
+    assert_eq!(stake(&app, &contract).bonded, Uint128::new(100));
+
+    // unbonding stake is still slashable
+    let unbond: Msg = ExecuteMsg::Unbond {
+        amount: Uint128::new(60),
+    };
+    app.execute_contract(Addr::unchecked(ORACLE), contract.clone(), &unbond, &[])
+        .unwrap();
+    respond(&mut app, &contract, "1").unwrap_err();
+
+    let slash: Msg = ExecuteMsg::Slash {
+        oracle: ORACLE.to_string(),
+        amount: Uint128::new(50),
+    };
+    app.execute_contract(Addr::unchecked(ORACLE), contract.clone(), &slash, &[])
+        .unwrap_err();
+    app.execute_contract(Addr::unchecked(OWNER), contract.clone(), &slash, &[])
+        .unwrap();
+    let stake_left = stake(&app, &contract);
+    assert_eq!(stake_left.bonded, Uint128::zero());
+    assert_eq!(stake_left.unbonding[0].amount, Uint128::new(50));
+
+    let claim: Msg = ExecuteMsg::Claim {};
+    app.execute_contract(Addr::unchecked(ORACLE), contract.clone(), &claim, &[])
+        .unwrap_err();
+    app.update_block(|block| block.height += 10);
+    app.execute_contract(Addr::unchecked(ORACLE), contract.clone(), &claim, &[])
+        .unwrap();
+    assert_eq!(balance(&app, ORACLE), 1000 - 200 + 50);
+    assert_eq!(balance(&app, contract.as_str()), 0);
+
+    // a removed oracle can't register again by bonding
+    let remove: Msg = ExecuteMsg::RemoveOracle {
+        oracle: ORACLE.to_string(),
+    };
+    app.execute_contract(Addr::unchecked(OWNER), contract.clone(), &remove, &[])
+        .unwrap();
+    let metadata = OracleMetadata {
+        name: "GPU farm".to_string(),
+        endpoint: "https://gpu.example.com".to_string(),
+        key: None,
+        models: vec![],
+    };
+    app.execute_contract(
+        Addr::unchecked(ORACLE),
+        contract.clone(),
+        &bond(Some(metadata)),
+        &coins(100, DENOM),
+    )
+    .unwrap_err();
+    register(&mut app, &contract);
+    app.execute_contract(
+        Addr::unchecked(ORACLE),
+        contract.clone(),
+        &bond(None),
+        &coins(100, DENOM),
+    )
+    .unwrap();
+}
+
+#[test]
+fn callback_on_fulfillment() {
+    let mut app = App::default();
+    let contract = instantiate(
+        &mut app,
+        InstantiateMsg {
+            callback_gas_limit: Some(100_000),
+            ..init_msg()
+        },
+    );
+    let code_id = app.store_code(Box::new(ContractWrapper::new(
+        receiver::execute,
+        receiver::instantiate,
+        receiver::query,
+    )));
+    let receiver = app
+        .instantiate_contract(
+            code_id,
+            Addr::unchecked(OWNER),
+            &Empty {},
+            &[],
+            "receiver",
+            None,
+        )
+        .unwrap();
+
+    register(&mut app, &contract);
+    let msg: Msg = ExecuteMsg::RegisterOracle {
+        oracle: receiver.to_string(),
+        metadata: OracleMetadata {
+            name: "Receiver".to_string(),
+            endpoint: "https://receiver.example.com".to_string(),
+            key: None,
+            models: vec![],
+        },
+    };
+    app.execute_contract(Addr::unchecked(OWNER), contract.clone(), &msg, &[])
+        .unwrap();
+
+    for payload in ["ok", "fail", "nested", "ok"] {
+        let msg: Msg = ExecuteMsg::Mint {
+            token_id: "Not used".to_string(),
+            owner: "john".to_string(),
+            token_uri: None,
+            extension: Some(Metadata {
+                description: Some("Hello".into()),
+                ..Metadata::default()
+            }),
+            prompt: "Hello".to_string(),
+            variables: None,
+            callback: Some(Callback {
+                contract: receiver.to_string(),
+                payload: Binary::from(payload.as_bytes()),
+            }),
+            prompt_id: None,
+        };
+        app.execute_contract(Addr::unchecked("john"), contract.clone(), &msg, &[])
+            .unwrap();
+    }
+
+    respond(&mut app, &contract, "0").unwrap();
+    assert_eq!(
+        receiver::last(&app, &receiver),
+        Cw7007ReceiveMsg {
+            token_id: "0".to_string(),
+            output: "WOOF".to_string(),
+            payload: Binary::from(b"ok"),
+        }
+    );
+
+    // a failing callback does not revert the response
+    let res = app
+        .execute_contract(
+            Addr::unchecked(ORACLE),
+            contract.clone(),
+            &Msg::Response {
+                token_id: "1".to_string(),
+                output: "WOOF".to_string(),
+                attestation: None,
+                generation: None,
+            },
+            &[],
+        )
+        .unwrap();
+    assert!(res.has_event(
+        &Event::new("wasm")
+            .add_attribute("action", "callback_failed")
+            .add_attribute("token_id", "1")
+    ));
+    let request: Request = app
+        .wrap()
+        .query_wasm_smart(
+            &contract,
+            &QueryMsg::<Empty>::Request {
+                token_id: "1".to_string(),
+            },
+        )
+        .unwrap();
+    assert_eq!(request.status, RequestStatus::Fulfilled);
+
+    // a callback finalizing another request before failing is told apart from it,
+    // and what it did is reverted
+    let res = app
+        .execute_contract(
+            Addr::unchecked(ORACLE),
+            contract.clone(),
+            &Msg::Response {
+                token_id: "2".to_string(),
+                output: "WOOF".to_string(),
+                attestation: None,
+                generation: None,
+            },
+            &[],
+        )
+        .unwrap();
+    assert!(res.has_event(
+        &Event::new("wasm")
+            .add_attribute("action", "callback_failed")
+            .add_attribute("token_id", "2")
+    ));
+    let request: Request = app
+        .wrap()
+        .query_wasm_smart(
+            &contract,
+            &QueryMsg::<Empty>::Request {
+                token_id: "3".to_string(),
+            },
+        )
+        .unwrap();
+    assert_eq!(request.status, RequestStatus::Pending);
+
+    // nothing is left in flight once replied
+    let callback_tokens =
+        Cw7007Contract::<Extension, Empty, Empty, Empty>::default().callback_tokens;
+    for reply_id in 1..=4 {
+        let in_flight = app
+            .wrap()
+            .query_wasm_raw(&contract, callback_tokens.key(reply_id).to_vec())
+            .unwrap();
+        assert_eq!(in_flight, None);
+    }
+}
+
+#[test]
+fn hooks_notified() {
+    let mut app = App::default();
+    let contract = instantiate(&mut app, init_msg());
+    let code_id = app.store_code(Box::new(ContractWrapper::new(
+        hook::execute,
+        hook::instantiate,
+        hook::query,
+    )));
+    let hook = app
+        .instantiate_contract(
+            code_id,
+            Addr::unchecked(OWNER),
+            &Empty {},
+            &[],
+            "hook",
+            None,
+        )
+        .unwrap();
+    register(&mut app, &contract);
+
+    // only the owner manages hooks
+    let add: Msg = ExecuteMsg::AddHook {
+        addr: hook.to_string(),
+    };
+    app.execute_contract(Addr::unchecked("john"), contract.clone(), &add, &[])
+        .unwrap_err();
+    app.execute_contract(Addr::unchecked(OWNER), contract.clone(), &add, &[])
+        .unwrap();
+    let hooks: HooksResponse = app
+        .wrap()
+        .query_wasm_smart(&contract, &QueryMsg::<Empty>::Hooks {})
+        .unwrap();
+    assert_eq!(hooks.hooks, vec![hook.to_string()]);
+
+    mint(&mut app, &contract);
+    respond(&mut app, &contract, "0").unwrap();
+    let burn: Msg = ExecuteMsg::Burn {
+        token_id: "0".to_string(),
+    };
+    app.execute_contract(Addr::unchecked("john"), contract.clone(), &burn, &[])
+        .unwrap();
+
+    let received: Vec<Cw7007HookMsg> = app.wrap().query_wasm_smart(&hook, &Empty {}).unwrap();
+    assert_eq!(
+        received,
+        vec![
+            Cw7007HookMsg::Mint {
+                token_id: "0".to_string(),
+                requester: "john".to_string(),
+                owner: "john".to_string(),
+            },
+            Cw7007HookMsg::Response {
+                token_id: "0".to_string(),
+                oracle: ORACLE.to_string(),
+                output: "WOOF".to_string(),
+            },
+            Cw7007HookMsg::Burn {
+                token_id: "0".to_string(),
+                sender: "john".to_string(),
+            },
+        ]
+    );
+
+    // removed hooks are not notified anymore
+    let remove: Msg = ExecuteMsg::RemoveHook {
+        addr: hook.to_string(),
+    };
+    app.execute_contract(Addr::unchecked(OWNER), contract.clone(), &remove, &[])
+        .unwrap();
+    mint(&mut app, &contract);
+    let received: Vec<Cw7007HookMsg> = app.wrap().query_wasm_smart(&hook, &Empty {}).unwrap();
+    assert_eq!(received.len(), 3);
+}
+
+fn legacy_contract(app: &mut App, version: &str) -> (Addr, u64) {
+    let legacy_code_id = app.store_code(Box::new(ContractWrapper::new(
+        legacy::execute,
+        legacy::instantiate,
+        legacy::query,
+    )));
+    let contract = app
+        .instantiate_contract(
+            legacy_code_id,
+            Addr::unchecked(OWNER),
+            &legacy::InstantiateMsg {
+                version: version.to_string(),
+            },
+            &[],
+            "cw7007",
+            Some(OWNER.to_string()),
+        )
+        .unwrap();
+    let code_id = app.store_code(Box::new(
+        ContractWrapper::new(entry::execute, entry::instantiate, entry::query)
+            .with_reply(entry::reply)
+            .with_migrate(entry::migrate),
+    ));
+    (contract, code_id)
+}
+
+#[test]
+fn migrate_from_v0_1() {
+    let mut app = App::default();
+    let (contract, code_id) = legacy_contract(&mut app, "0.1.0");
+    // a batch too small for every token leaves the stored version behind
+    app.migrate_contract(
+        Addr::unchecked(OWNER),
+        contract.clone(),
+        &MigrateMsg { limit: Some(1) },
+        code_id,
+    )
+    .unwrap();
+    let version = cw2::query_contract_info(&app.wrap(), &contract).unwrap();
+    assert_eq!(version.version, "0.1.0");
+    app.migrate_contract(
+        Addr::unchecked(OWNER),
+        contract.clone(),
+        &MigrateMsg { limit: None },
+        code_id,
+    )
+    .unwrap();
+    let version = cw2::query_contract_info(&app.wrap(), &contract).unwrap();
+    assert_eq!(version.version, env!("CARGO_PKG_VERSION"));
+
+    let prompt: PromptInfoResponse = app
+        .wrap()
+        .query_wasm_smart(&contract, &QueryMsg::<Empty>::Prompt {})
+        .unwrap();
+    assert_eq!(prompt.prompt, "You are a cat.");
+    assert_eq!(prompt.version, 1);
+
+    // the legacy id list is now the pending index of the request records
+    let res: RequestIdsResponse = app
+        .wrap()
+        .query_wasm_smart(
+            &contract,
+            &QueryMsg::<Empty>::RequestIds {
+                start_after: None,
+                limit: None,
+            },
+        )
+        .unwrap();
+    assert_eq!(res.ids, vec!["1".to_string()]);
+    let request: Request = app
+        .wrap()
+        .query_wasm_smart(
+            &contract,
+            &QueryMsg::<Empty>::Request {
+                token_id: "0".to_string(),
+            },
+        )
+        .unwrap();
+    assert_eq!(request.status, RequestStatus::Fulfilled);
+    let record: AigcData = app
+        .wrap()
+        .query_wasm_smart(
+            &contract,
+            &QueryMsg::<Empty>::AigcData {
+                token_id: "0".to_string(),
+            },
+        )
+        .unwrap();
+    assert_eq!(record.prompt, "Hello");
+    assert_eq!(record.aigc_data, Some("MEOW".to_string()));
+
+    // the migrated contract keeps serving requests, without reusing ids
+    register(&mut app, &contract);
+    respond(&mut app, &contract, "1").unwrap();
+    mint(&mut app, &contract);
+    let request: Request = app
+        .wrap()
+        .query_wasm_smart(
+            &contract,
+            &QueryMsg::<Empty>::Request {
+                token_id: "2".to_string(),
+            },
+        )
+        .unwrap();
+    assert_eq!(request.status, RequestStatus::Pending);
+
+    // migrating to the same version has nothing left to do
+    app.migrate_contract(
+        Addr::unchecked(OWNER),
+        contract,
+        &MigrateMsg { limit: None },
+        code_id,
+    )
+    .unwrap();
+}
+
+#[test]
+fn migrate_refuses_downgrade() {
+    let mut app = App::default();
+    let (contract, code_id) = legacy_contract(&mut app, "9.0.0");
+    app.migrate_contract(
+        Addr::unchecked(OWNER),
+        contract,
+        &MigrateMsg { limit: None },
+        code_id,
+    )
+    .unwrap_err();
+}
@@ -0,0 +1,164 @@
+use cosmwasm_std::{BankMsg, CustomMsg, DepsMut, Env, MessageInfo, Response, StdError, Storage};
+use cw721_base::ContractError;
+use cw_ownable::OwnershipError;
+
+use crate::msg::OptimisticConfig;
+use crate::state::{Challenge, Cw7007Contract, Extension, Proposal};
+
+impl<'a, C, E, Q> Cw7007Contract<'a, Extension, C, E, Q>
+where
+    C: CustomMsg,
+    E: CustomMsg,
+    Q: CustomMsg,
+{
+    pub fn challenge_response(
+        &self,
+        deps: DepsMut,
+        env: Env,
+        info: MessageInfo,
+        token_id: String,
+    ) -> Result<Response<C>, ContractError> {
+        let config = self.load_optimistic(deps.storage)?;
+        let mut proposal = self.load_proposal(deps.storage, &token_id)?;
+        if proposal.challenge.is_some() {
+            return Err(ContractError::Std(StdError::generic_err(
+                "proposal is already challenged.",
+            )));
+        }
+        if env.block.height > proposal.challenge_deadline {
+            return Err(ContractError::Std(StdError::generic_err(
+                "challenge window is over.",
+            )));
+        }
+
+        let paid = cw_utils::must_pay(&info, &config.challenge_bond.denom)
+            .map_err(|err| StdError::generic_err(err.to_string()))?;
+        if paid != config.challenge_bond.amount {
+            return Err(ContractError::Std(StdError::generic_err(format!(
+                "challenge bond must be {}.",
+                config.challenge_bond
+            ))));
+        }
+
+        proposal.challenge = Some(Challenge {
+            challenger: info.sender.clone(),
+            bond: config.challenge_bond,
+            challenged_at: env.block.height,
+        });
+        self.proposals.save(deps.storage, &token_id, &proposal)?;
+
+        Ok(Response::new()
+            .add_attribute("action", "challenge_response")
+            .add_attribute("sender", info.sender)
+            .add_attribute("token_id", token_id))
+    }
+
+    pub fn resolve_challenge(
+        &self,
+        deps: DepsMut,
+        env: Env,
+        info: MessageInfo,
+        token_id: String,
+        upheld: bool,
+    ) -> Result<Response<C>, ContractError> {
+        let config = self.load_optimistic(deps.storage)?;
+        match &config.arbiter {
+            Some(arbiter) if arbiter != info.sender.as_str() => {
+                return Err(ContractError::Ownership(OwnershipError::NotOwner));
+            }
+            Some(_) => {}
+            None => cw_ownable::assert_owner(deps.storage, &info.sender)?,
+        }
+
+        let proposal = self.load_proposal(deps.storage, &token_id)?;
+        let challenge = proposal
+            .challenge
+            .ok_or_else(|| StdError::generic_err("proposal is not challenged."))?;
+        self.proposals.remove(deps.storage, &token_id);
+
+        // an upheld challenge sends the request back to the oracles, slashing the proposer
+        let (res, bond_recipient) = if upheld {
+            let slashed =
+                self.slash_for_dispute(deps.storage, &proposal.proposer, &challenge.challenger)?;
+            // the request goes to another oracle than the one proven wrong
+            let mut request = self.requests.load(deps.storage, &token_id)?;
+            self.assign(deps.storage, &env, &mut request, Some(&proposal.proposer))?;
+            self.requests.save(deps.storage, &token_id, &request)?;
+            (Response::new().add_messages(slashed), challenge.challenger)
+        } else {
+            let res = self.finalize_response(
+                deps,
+                &env,
+                &proposal.proposer,
+                &token_id,
+                &proposal.output,
+                proposal.attestation,
+                proposal.generation,
+            )?;
+            (res, proposal.proposer)
+        };
+
+        Ok(res
+            .add_message(BankMsg::Send {
+                to_address: bond_recipient.to_string(),
+                amount: vec![challenge.bond],
+            })
+            .add_attribute("action", "resolve_challenge")
+            .add_attribute("sender", info.sender)
+            .add_attribute("token_id", token_id)
+            .add_attribute("upheld", upheld.to_string()))
+    }
+
+    pub fn finalize_proposal(
+        &self,
+        deps: DepsMut,
+        env: Env,
+        info: MessageInfo,
+        token_id: String,
+    ) -> Result<Response<C>, ContractError> {
+        let proposal = self.load_proposal(deps.storage, &token_id)?;
+        if proposal.challenge.is_some() {
+            return Err(ContractError::Std(StdError::generic_err(
+                "proposal is challenged.",
+            )));
+        }
+        if env.block.height <= proposal.challenge_deadline {
+            return Err(ContractError::Std(StdError::generic_err(
+                "challenge window is not over yet.",
+            )));
+        }
+
+        self.proposals.remove(deps.storage, &token_id);
+        let res = self.finalize_response(
+            deps,
+            &env,
+            &proposal.proposer,
+            &token_id,
+            &proposal.output,
+            proposal.attestation,
+            proposal.generation,
+        )?;
+
+        Ok(res
+            .add_attribute("action", "finalize_response")
+            .add_attribute("sender", info.sender)
+            .add_attribute("token_id", token_id)
+            .add_attribute("output", proposal.output))
+    }
+
+    fn load_optimistic(&self, storage: &dyn Storage) -> Result<OptimisticConfig, ContractError> {
+        self.optimistic
+            .load(storage)?
+            .ok_or_else(|| StdError::generic_err("optimistic mode is not enabled.").into())
+    }
+
+    fn load_proposal(
+        &self,
+        storage: &dyn Storage,
+        token_id: &str,
+    ) -> Result<Proposal, ContractError> {
+        self.proposals
+            .may_load(storage, token_id)?
+            .ok_or_else(|| StdError::generic_err("no output is proposed.").into())
+    }
+}
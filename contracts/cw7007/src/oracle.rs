@@ -0,0 +1,119 @@
+use cosmwasm_std::{Addr, CustomMsg, DepsMut, Empty, MessageInfo, Response, StdError, Storage};
+use cw721_base::ContractError;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+use crate::msg::OracleMetadata;
+use crate::state::{Cw7007Contract, Oracle};
+
+impl<'a, T, C, E, Q> Cw7007Contract<'a, T, C, E, Q>
+where
+    T: Serialize + DeserializeOwned + Clone,
+    C: CustomMsg,
+    E: CustomMsg,
+    Q: CustomMsg,
+{
+    pub fn register_oracle(
+        &self,
+        deps: DepsMut,
+        info: MessageInfo,
+        oracle: String,
+        metadata: OracleMetadata,
+    ) -> Result<Response<C>, ContractError> {
+        cw_ownable::assert_owner(deps.storage, &info.sender)?;
+
+        let oracle = deps.api.addr_validate(&oracle)?;
+        // updating the metadata of a paused oracle does not resume it
+        let record =
+            self.oracles
+                .update(deps.storage, &oracle, |old| -> Result<_, ContractError> {
+                    Ok(Oracle {
+                        metadata,
+                        active: old.is_none_or(|old| old.active),
+                    })
+                })?;
+        self.set_assignable(deps.storage, &oracle, record.active)?;
+        self.removed_oracles.remove(deps.storage, &oracle);
+
+        Ok(Response::new()
+            .add_attribute("action", "register_oracle")
+            .add_attribute("sender", info.sender)
+            .add_attribute("oracle", oracle))
+    }
+
+    pub fn remove_oracle(
+        &self,
+        deps: DepsMut,
+        info: MessageInfo,
+        oracle: String,
+    ) -> Result<Response<C>, ContractError> {
+        cw_ownable::assert_owner(deps.storage, &info.sender)?;
+
+        let oracle = deps.api.addr_validate(&oracle)?;
+        self.load_oracle(deps.storage, &oracle)?;
+        self.oracles.remove(deps.storage, &oracle);
+        self.set_assignable(deps.storage, &oracle, false)?;
+        self.removed_oracles
+            .save(deps.storage, &oracle, &Empty {})?;
+
+        Ok(Response::new()
+            .add_attribute("action", "remove_oracle")
+            .add_attribute("sender", info.sender)
+            .add_attribute("oracle", oracle))
+    }
+
+    /// Pause or resume an oracle
+    pub fn set_oracle_active(
+        &self,
+        deps: DepsMut,
+        info: MessageInfo,
+        oracle: String,
+        active: bool,
+    ) -> Result<Response<C>, ContractError> {
+        cw_ownable::assert_owner(deps.storage, &info.sender)?;
+
+        let oracle = deps.api.addr_validate(&oracle)?;
+        let mut record = self.load_oracle(deps.storage, &oracle)?;
+        record.active = active;
+        self.oracles.save(deps.storage, &oracle, &record)?;
+        self.set_assignable(deps.storage, &oracle, active)?;
+
+        Ok(Response::new()
+            .add_attribute(
+                "action",
+                if active {
+                    "resume_oracle"
+                } else {
+                    "pause_oracle"
+                },
+            )
+            .add_attribute("sender", info.sender)
+            .add_attribute("oracle", oracle))
+    }
+
+    /// Make sure `addr` is a registered oracle that is not paused, and staked if required
+    pub fn assert_oracle(
+        &self,
+        storage: &dyn Storage,
+        addr: &Addr,
+    ) -> Result<Oracle, ContractError> {
+        let oracle = self.load_oracle(storage, addr)?;
+        if !oracle.active {
+            return Err(ContractError::Std(StdError::generic_err(
+                "oracle is paused.",
+            )));
+        }
+        if !self.has_min_stake(storage, addr)? {
+            return Err(ContractError::Std(StdError::generic_err(
+                "oracle stake is below the minimum.",
+            )));
+        }
+        Ok(oracle)
+    }
+
+    fn load_oracle(&self, storage: &dyn Storage, addr: &Addr) -> Result<Oracle, ContractError> {
+        self.oracles
+            .may_load(storage, addr)?
+            .ok_or_else(|| StdError::generic_err("oracle is not registered.").into())
+    }
+}
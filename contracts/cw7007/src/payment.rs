@@ -0,0 +1,216 @@
+use cosmwasm_std::{
+    from_json, Addr, Api, Coin, CosmosMsg, CustomMsg, Decimal, DepsMut, Env, MessageInfo, Response,
+    StdError, StdResult, Storage, Uint128,
+};
+use cw20::Cw20ReceiveMsg;
+use cw721_base::ContractError;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+use crate::msg::{MintFeeConfig, ReceiveMsg};
+use crate::state::{Cw7007Contract, Escrow, Extension, Request, TreasuryCut};
+
+/// Make sure a fee config is consistent before storing it
+pub fn validate_mint_fee(api: &dyn Api, config: &MintFeeConfig) -> StdResult<()> {
+    if config.treasury_share > Decimal::one() {
+        return Err(StdError::generic_err("treasury share must not exceed 1."));
+    }
+    match &config.treasury {
+        Some(treasury) => {
+            api.addr_validate(treasury)?;
+        }
+        None if !config.treasury_share.is_zero() => {
+            return Err(StdError::generic_err(
+                "treasury is required for a non zero treasury share.",
+            ));
+        }
+        None => {}
+    }
+    validate_price(&config.price)
+}
+
+/// Make sure a price has one positive amount per denom
+pub fn validate_price(price: &[Coin]) -> StdResult<()> {
+    for (i, coin) in price.iter().enumerate() {
+        if coin.amount.is_zero() {
+            return Err(StdError::generic_err(
+                "price must not contain zero amounts.",
+            ));
+        }
+        if price[..i].iter().any(|c| c.denom == coin.denom) {
+            return Err(StdError::generic_err("price must not repeat a denom."));
+        }
+    }
+    Ok(())
+}
+
+/// Sort coins by denom so that payments can be compared regardless of order
+fn normalized(coins: &[Coin]) -> Vec<Coin> {
+    let mut coins = coins.to_vec();
+    coins.sort_by(|a, b| a.denom.cmp(&b.denom));
+    coins
+}
+
+impl<'a, T, C, E, Q> Cw7007Contract<'a, T, C, E, Q>
+where
+    T: Serialize + DeserializeOwned + Clone,
+    C: CustomMsg,
+    E: CustomMsg,
+    Q: CustomMsg,
+{
+    /// Check the funds sent along a mint against the price of its prompt,
+    /// returning what goes into escrow
+    pub fn collect_mint_fee(
+        &self,
+        storage: &dyn Storage,
+        info: &MessageInfo,
+        prompt_id: Option<&str>,
+    ) -> Result<Escrow, ContractError> {
+        let prompt_price = match prompt_id {
+            Some(prompt_id) => self.load_open_prompt(storage, prompt_id)?.price,
+            None => None,
+        };
+        let price = match (prompt_price, self.mint_fee.load(storage)?) {
+            (Some(price), _) => price,
+            (None, Some(config)) => config.price,
+            (None, None) => vec![],
+        };
+        if normalized(&info.funds) != normalized(&price) {
+            return Err(ContractError::Std(StdError::generic_err(format!(
+                "mint price is [{}].",
+                price
+                    .iter()
+                    .map(Coin::to_string)
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ))));
+        }
+        Ok(Escrow::Native { coins: price })
+    }
+
+    /// Treasury cut of the current fee config, fixed on a request at mint
+    pub fn treasury_cut(&self, storage: &dyn Storage) -> StdResult<Option<TreasuryCut>> {
+        Ok(match self.mint_fee.load(storage)? {
+            Some(MintFeeConfig {
+                treasury: Some(treasury),
+                treasury_share,
+                ..
+            }) => Some(TreasuryCut {
+                treasury: Addr::unchecked(treasury),
+                share: treasury_share,
+            }),
+            _ => None,
+        })
+    }
+
+    /// Pay the escrow of a fulfilled request out to the oracle, minus the treasury share
+    /// it was minted with
+    pub fn release_escrow(&self, request: &Request, oracle: &Addr) -> StdResult<Vec<CosmosMsg<C>>> {
+        let (to_treasury, to_oracle) = match &request.treasury_cut {
+            Some(TreasuryCut { treasury, share }) => {
+                let (cut, rest) = request.escrow.split(*share);
+                (cut.send_msg(treasury)?, rest)
+            }
+            None => (None, request.escrow.clone()),
+        };
+
+        Ok(to_oracle
+            .send_msg(oracle)?
+            .into_iter()
+            .chain(to_treasury)
+            .collect())
+    }
+
+    pub fn update_mint_fee(
+        &self,
+        deps: DepsMut,
+        info: MessageInfo,
+        mint_fee: Option<MintFeeConfig>,
+    ) -> Result<Response<C>, ContractError> {
+        cw_ownable::assert_owner(deps.storage, &info.sender)?;
+
+        if let Some(config) = &mint_fee {
+            validate_mint_fee(deps.api, config)?;
+        }
+        self.mint_fee.save(deps.storage, &mint_fee)?;
+
+        Ok(Response::new()
+            .add_attribute("action", "update_mint_fee")
+            .add_attribute("sender", info.sender))
+    }
+
+    pub fn update_cw20_price(
+        &self,
+        deps: DepsMut,
+        info: MessageInfo,
+        token: String,
+        price: Option<Uint128>,
+    ) -> Result<Response<C>, ContractError> {
+        cw_ownable::assert_owner(deps.storage, &info.sender)?;
+
+        let token = deps.api.addr_validate(&token)?;
+        match price {
+            Some(price) if price.is_zero() => {
+                return Err(ContractError::Std(StdError::generic_err(
+                    "price must not be zero.",
+                )));
+            }
+            Some(price) => self.cw20_prices.save(deps.storage, &token, &price)?,
+            None => self.cw20_prices.remove(deps.storage, &token),
+        }
+
+        Ok(Response::new()
+            .add_attribute("action", "update_cw20_price")
+            .add_attribute("sender", info.sender)
+            .add_attribute("token", token))
+    }
+}
+
+impl<'a, C, E, Q> Cw7007Contract<'a, Extension, C, E, Q>
+where
+    C: CustomMsg,
+    E: CustomMsg,
+    Q: CustomMsg,
+{
+    /// Mint paid with a whitelisted CW20 token. `info.sender` is the token contract
+    pub fn receive_cw20(
+        &self,
+        deps: DepsMut,
+        env: Env,
+        info: MessageInfo,
+        wrapper: Cw20ReceiveMsg,
+    ) -> Result<Response<C>, ContractError> {
+        let price = self
+            .cw20_prices
+            .may_load(deps.storage, &info.sender)?
+            .ok_or_else(|| StdError::generic_err("token is not accepted."))?;
+        if wrapper.amount != price {
+            return Err(ContractError::Std(StdError::generic_err(format!(
+                "mint price is {}{}.",
+                price, info.sender
+            ))));
+        }
+
+        let requester = deps.api.addr_validate(&wrapper.sender)?;
+        let escrow = Escrow::Cw20 {
+            token: info.sender,
+            amount: wrapper.amount,
+        };
+        match from_json(&wrapper.msg)? {
+            ReceiveMsg::Mint(msg) => {
+                if let Some(prompt_id) = &msg.prompt_id {
+                    if self
+                        .load_open_prompt(deps.storage, prompt_id)?
+                        .price
+                        .is_some()
+                    {
+                        return Err(ContractError::Std(StdError::generic_err(
+                            "prompt is only sold for native coins.",
+                        )));
+                    }
+                }
+                self.mint_request(deps, env, requester, escrow, msg)
+            }
+        }
+    }
+}
@@ -0,0 +1,165 @@
+use cosmwasm_std::{
+    BlockInfo, Coin, CustomMsg, DepsMut, Env, MessageInfo, Response, StdError, StdResult, Storage,
+};
+use cw721_base::ContractError;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+use crate::msg::PromptInfoResponse;
+use crate::payment::validate_price;
+use crate::state::{AigcData, Cw7007Contract, PromptVersion, RegisteredPrompt};
+
+/// Longest id of a registered prompt
+const MAX_PROMPT_ID_LENGTH: usize = 64;
+
+impl<'a, T, C, E, Q> Cw7007Contract<'a, T, C, E, Q>
+where
+    T: Serialize + DeserializeOwned + Clone,
+    C: CustomMsg,
+    E: CustomMsg,
+    Q: CustomMsg,
+{
+    /// Make `prompt` the current collection prompt, under a new version
+    pub fn publish_prompt(
+        &self,
+        storage: &mut dyn Storage,
+        block: &BlockInfo,
+        prompt: String,
+    ) -> StdResult<u32> {
+        let version = match self.prompt_info.may_load(storage)? {
+            Some(current) => current.version + 1,
+            None => 1,
+        };
+        self.prompt_versions.save(
+            storage,
+            version,
+            &PromptVersion {
+                version,
+                prompt: prompt.clone(),
+                created_at: block.time,
+            },
+        )?;
+        self.prompt_info
+            .save(storage, &PromptInfoResponse { prompt, version })?;
+        Ok(version)
+    }
+
+    pub fn update_prompt(
+        &self,
+        deps: DepsMut,
+        env: Env,
+        info: MessageInfo,
+        prompt: String,
+    ) -> Result<Response<C>, ContractError> {
+        cw_ownable::assert_owner(deps.storage, &info.sender)?;
+
+        let version = self.publish_prompt(deps.storage, &env.block, prompt)?;
+
+        Ok(Response::new()
+            .add_attribute("action", "update_prompt")
+            .add_attribute("sender", info.sender)
+            .add_attribute("version", version.to_string()))
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    pub fn add_prompt(
+        &self,
+        deps: DepsMut,
+        env: Env,
+        info: MessageInfo,
+        prompt_id: String,
+        name: String,
+        prompt: String,
+        price: Option<Vec<Coin>>,
+    ) -> Result<Response<C>, ContractError> {
+        cw_ownable::assert_owner(deps.storage, &info.sender)?;
+
+        if prompt_id.is_empty()
+            || prompt_id.len() > MAX_PROMPT_ID_LENGTH
+            || !prompt_id
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+        {
+            return Err(ContractError::Std(StdError::generic_err(format!(
+                "prompt id must be 1 to {} ascii letters, digits, '-' or '_'.",
+                MAX_PROMPT_ID_LENGTH
+            ))));
+        }
+        if self.prompts.has(deps.storage, &prompt_id) {
+            return Err(ContractError::Std(StdError::generic_err(
+                "prompt id is already registered.",
+            )));
+        }
+        if prompt.is_empty() {
+            return Err(ContractError::Std(StdError::generic_err(
+                "prompt must not be empty.",
+            )));
+        }
+        if let Some(price) = &price {
+            validate_price(price)?;
+        }
+
+        self.prompts.save(
+            deps.storage,
+            &prompt_id,
+            &RegisteredPrompt {
+                id: prompt_id.clone(),
+                name,
+                prompt,
+                price,
+                open: true,
+                created_at: env.block.time,
+            },
+        )?;
+
+        Ok(Response::new()
+            .add_attribute("action", "add_prompt")
+            .add_attribute("sender", info.sender)
+            .add_attribute("prompt_id", prompt_id))
+    }
+
+    pub fn close_prompt(
+        &self,
+        deps: DepsMut,
+        info: MessageInfo,
+        prompt_id: String,
+    ) -> Result<Response<C>, ContractError> {
+        cw_ownable::assert_owner(deps.storage, &info.sender)?;
+
+        let mut prompt = self.load_open_prompt(deps.storage, &prompt_id)?;
+        prompt.open = false;
+        self.prompts.save(deps.storage, &prompt_id, &prompt)?;
+
+        Ok(Response::new()
+            .add_attribute("action", "close_prompt")
+            .add_attribute("sender", info.sender)
+            .add_attribute("prompt_id", prompt_id))
+    }
+
+    /// Registered prompt that can still be minted
+    pub fn load_open_prompt(
+        &self,
+        storage: &dyn Storage,
+        prompt_id: &str,
+    ) -> StdResult<RegisteredPrompt> {
+        let prompt = self
+            .prompts
+            .may_load(storage, prompt_id)?
+            .ok_or_else(|| StdError::generic_err("prompt is not registered."))?;
+        if !prompt.open {
+            return Err(StdError::generic_err("prompt is closed."));
+        }
+        Ok(prompt)
+    }
+
+    /// Registered or collection prompt `record` was minted under
+    pub fn token_prompt(&self, storage: &dyn Storage, record: &AigcData) -> StdResult<String> {
+        match &record.prompt_id {
+            Some(prompt_id) => Ok(self.prompts.load(storage, prompt_id)?.prompt),
+            None => Ok(self
+                .prompt_versions
+                .load(storage, record.prompt_version)?
+                .prompt),
+        }
+    }
+}
@@ -2,13 +2,29 @@ use schemars::JsonSchema;
 use serde::de::DeserializeOwned;
 use serde::Serialize;
 
-use cosmwasm_std::{to_binary, Binary, CustomMsg, Deps, Env, StdResult};
+use cosmwasm_std::{to_json_binary, Addr, Binary, CustomMsg, Deps, Env, Order, StdResult};
+use cw_controllers::HooksResponse;
+use cw_storage_plus::Bound;
+use cw_utils::Duration;
 
-use crate::msg::{PromptInfoResponse, QueryMsg};
-use crate::state::Cw7007Contract;
+use crate::msg::{
+    AssignmentConfig, CommitmentInfo, CommitmentsResponse, Cw20PriceInfo, Cw20PricesResponse,
+    Generation, MintFeeConfig, ModelInfo, ModelsResponse, OptimisticConfig, OracleInfo,
+    OraclesResponse, PromptInfoResponse, PromptTemplate, PromptVersionsResponse, QueryMsg,
+    QuorumConfig, RegisteredPromptsResponse, RequestIdsResponse, StakingConfig, TokenIdStrategy,
+    VerificationResponse, VerificationStatus, VerifierConfig, Vote, VotesResponse,
+};
+use crate::state::{
+    AigcData, Cw7007Contract, Oracle, PromptVersion, Proposal, RegisteredPrompt, Reputation,
+    Request, RequestStatus, Stake,
+};
 use crate::traits::Cw7007Query;
+use cw721::TokensResponse;
 use cw721_base::QueryMsg as Cw721QueryMsg;
 
+const DEFAULT_LIMIT: u32 = 10;
+const MAX_LIMIT: u32 = 1000;
+
 impl<'a, T, C, E, Q> Cw7007Query<T> for Cw7007Contract<'a, T, C, E, Q>
 where
     T: Serialize + DeserializeOwned + Clone,
@@ -20,8 +36,300 @@ where
         self.prompt_info.load(deps.storage)
     }
 
-    fn request_ids(&self, deps: Deps) -> StdResult<crate::msg::RequestIdsResponse> {
-        self.request_ids.load(deps.storage)
+    fn request_ids(
+        &self,
+        deps: Deps,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    ) -> StdResult<RequestIdsResponse> {
+        let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+        let start = start_after.map(Bound::exclusive);
+
+        let ids = self
+            .requests
+            .idx
+            .status
+            .prefix(RequestStatus::Pending.key())
+            .keys(deps.storage, start, None, Order::Ascending)
+            .take(limit)
+            .collect::<StdResult<_>>()?;
+
+        Ok(RequestIdsResponse { ids })
+    }
+
+    fn request(&self, deps: Deps, token_id: String) -> StdResult<Request> {
+        self.requests.load(deps.storage, &token_id)
+    }
+
+    fn aigc_data(&self, deps: Deps, token_id: String) -> StdResult<AigcData> {
+        self.aigc_data.load(deps.storage, &token_id)
+    }
+
+    fn request_timeout(&self, deps: Deps) -> StdResult<Option<Duration>> {
+        self.request_timeout.load(deps.storage)
+    }
+
+    fn token_id_strategy(&self, deps: Deps) -> StdResult<TokenIdStrategy> {
+        self.token_id_strategy.load(deps.storage)
+    }
+
+    fn prompt_template(&self, deps: Deps) -> StdResult<Option<PromptTemplate>> {
+        self.prompt_template.load(deps.storage)
+    }
+
+    fn registered_prompt(&self, deps: Deps, prompt_id: String) -> StdResult<RegisteredPrompt> {
+        self.prompts.load(deps.storage, &prompt_id)
+    }
+
+    fn registered_prompts(
+        &self,
+        deps: Deps,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    ) -> StdResult<RegisteredPromptsResponse> {
+        let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+        let start = start_after.as_deref().map(Bound::exclusive);
+
+        let prompts = self
+            .prompts
+            .range(deps.storage, start, None, Order::Ascending)
+            .take(limit)
+            .map(|item| item.map(|(_, prompt)| prompt))
+            .collect::<StdResult<_>>()?;
+
+        Ok(RegisteredPromptsResponse { prompts })
+    }
+
+    fn tokens_by_prompt(
+        &self,
+        deps: Deps,
+        prompt_id: String,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    ) -> StdResult<TokensResponse> {
+        let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+        let start = start_after.as_deref().map(Bound::exclusive);
+
+        let tokens = self
+            .prompt_tokens
+            .prefix(prompt_id.as_str())
+            .keys(deps.storage, start, None, Order::Ascending)
+            .take(limit)
+            .collect::<StdResult<_>>()?;
+
+        Ok(TokensResponse { tokens })
+    }
+
+    fn models(
+        &self,
+        deps: Deps,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    ) -> StdResult<ModelsResponse> {
+        let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+        let start = start_after.as_deref().map(Bound::exclusive);
+
+        let models = self
+            .models
+            .range(deps.storage, start, None, Order::Ascending)
+            .take(limit)
+            .map(|item| {
+                item.map(|(model_id, weights_hash)| ModelInfo {
+                    model_id,
+                    weights_hash,
+                })
+            })
+            .collect::<StdResult<_>>()?;
+
+        Ok(ModelsResponse { models })
+    }
+
+    fn generation(&self, deps: Deps, token_id: String) -> StdResult<Option<Generation>> {
+        Ok(self.aigc_data.load(deps.storage, &token_id)?.generation)
+    }
+
+    fn prompt_version(&self, deps: Deps, version: u32) -> StdResult<PromptVersion> {
+        self.prompt_versions.load(deps.storage, version)
+    }
+
+    fn prompt_versions(
+        &self,
+        deps: Deps,
+        start_after: Option<u32>,
+        limit: Option<u32>,
+    ) -> StdResult<PromptVersionsResponse> {
+        let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+        let start = start_after.map(Bound::exclusive);
+
+        let versions = self
+            .prompt_versions
+            .range(deps.storage, start, None, Order::Ascending)
+            .take(limit)
+            .map(|item| item.map(|(_, version)| version))
+            .collect::<StdResult<_>>()?;
+
+        Ok(PromptVersionsResponse { versions })
+    }
+
+    fn mint_fee(&self, deps: Deps) -> StdResult<Option<MintFeeConfig>> {
+        self.mint_fee.load(deps.storage)
+    }
+
+    fn verifier(&self, deps: Deps) -> StdResult<Option<VerifierConfig>> {
+        self.verifier.load(deps.storage)
+    }
+
+    fn verification(&self, deps: Deps, token_id: String) -> StdResult<VerificationResponse> {
+        let status = self
+            .verifications
+            .may_load(deps.storage, &token_id)?
+            .unwrap_or(VerificationStatus::Unverified);
+        Ok(VerificationResponse { token_id, status })
+    }
+
+    fn optimistic(&self, deps: Deps) -> StdResult<Option<OptimisticConfig>> {
+        self.optimistic.load(deps.storage)
+    }
+
+    fn proposal(&self, deps: Deps, token_id: String) -> StdResult<Option<Proposal>> {
+        self.proposals.may_load(deps.storage, &token_id)
+    }
+
+    fn verifying_key(&self, deps: Deps) -> StdResult<Option<Binary>> {
+        self.verifying_key.may_load(deps.storage)
+    }
+
+    fn staking(&self, deps: Deps) -> StdResult<Option<StakingConfig>> {
+        self.staking.load(deps.storage)
+    }
+
+    fn stake(&self, deps: Deps, oracle: String) -> StdResult<Stake> {
+        let oracle = deps.api.addr_validate(&oracle)?;
+        Ok(self
+            .stakes
+            .may_load(deps.storage, &oracle)?
+            .unwrap_or_default())
+    }
+
+    fn assignment(&self, deps: Deps) -> StdResult<Option<AssignmentConfig>> {
+        self.assignment.load(deps.storage)
+    }
+
+    fn assigned_requests(
+        &self,
+        deps: Deps,
+        oracle: String,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    ) -> StdResult<RequestIdsResponse> {
+        let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+        let oracle = deps.api.addr_validate(&oracle)?;
+        let start = start_after.map(Bound::exclusive);
+
+        let ids = self
+            .requests
+            .idx
+            .assignee_status
+            .prefix((oracle.to_string(), RequestStatus::Pending.key()))
+            .range(deps.storage, start, None, Order::Ascending)
+            .take(limit)
+            .map(|item| item.map(|(token_id, _)| token_id))
+            .collect::<StdResult<_>>()?;
+
+        Ok(RequestIdsResponse { ids })
+    }
+
+    fn quorum(&self, deps: Deps) -> StdResult<Option<QuorumConfig>> {
+        self.quorum.load(deps.storage)
+    }
+
+    fn votes(&self, deps: Deps, token_id: String) -> StdResult<VotesResponse> {
+        let votes = self
+            .votes
+            .prefix(&token_id)
+            .range(deps.storage, None, None, Order::Ascending)
+            .map(|item| {
+                item.map(|(oracle, output_hash)| Vote {
+                    oracle,
+                    output_hash,
+                })
+            })
+            .collect::<StdResult<_>>()?;
+        let agreed = self.agreed_outputs.may_load(deps.storage, &token_id)?;
+
+        Ok(VotesResponse { votes, agreed })
+    }
+
+    fn reputation(&self, deps: Deps, oracle: String) -> StdResult<Reputation> {
+        let oracle = deps.api.addr_validate(&oracle)?;
+        Ok(self
+            .reputations
+            .may_load(deps.storage, &oracle)?
+            .unwrap_or_default())
+    }
+
+    fn reveal_window(&self, deps: Deps) -> StdResult<Option<Duration>> {
+        self.reveal_window.load(deps.storage)
+    }
+
+    fn commitments(&self, deps: Deps, token_id: String) -> StdResult<CommitmentsResponse> {
+        let commitments = self
+            .commitments
+            .prefix(&token_id)
+            .range(deps.storage, None, None, Order::Ascending)
+            .map(|item| item.map(|(oracle, commitment)| CommitmentInfo { oracle, commitment }))
+            .collect::<StdResult<_>>()?;
+
+        Ok(CommitmentsResponse { commitments })
+    }
+
+    fn oracle(&self, deps: Deps, address: String) -> StdResult<Option<Oracle>> {
+        let address = deps.api.addr_validate(&address)?;
+        self.oracles.may_load(deps.storage, &address)
+    }
+
+    fn oracles(
+        &self,
+        deps: Deps,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    ) -> StdResult<OraclesResponse> {
+        let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+        let start_addr = start_after.map(Addr::unchecked);
+        let start = start_addr.as_ref().map(Bound::exclusive);
+
+        let oracles = self
+            .oracles
+            .range(deps.storage, start, None, Order::Ascending)
+            .take(limit)
+            .map(|item| item.map(|(address, oracle)| OracleInfo { address, oracle }))
+            .collect::<StdResult<_>>()?;
+
+        Ok(OraclesResponse { oracles })
+    }
+
+    fn cw20_prices(
+        &self,
+        deps: Deps,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    ) -> StdResult<Cw20PricesResponse> {
+        let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+        let start_addr = start_after.map(Addr::unchecked);
+        let start = start_addr.as_ref().map(Bound::exclusive);
+
+        let prices = self
+            .cw20_prices
+            .range(deps.storage, start, None, Order::Ascending)
+            .take(limit)
+            .map(|item| item.map(|(token, price)| Cw20PriceInfo { token, price }))
+            .collect::<StdResult<_>>()?;
+
+        Ok(Cw20PricesResponse { prices })
+    }
+
+    fn hooks(&self, deps: Deps) -> StdResult<HooksResponse> {
+        self.hooks.query_hooks(deps)
     }
 }
 
@@ -34,8 +342,67 @@ where
 {
     pub fn query(&self, deps: Deps, env: Env, msg: QueryMsg<Q>) -> StdResult<Binary> {
         match msg {
-            QueryMsg::Prompt {} => to_binary(&self.prompt_info(deps)?),
-            QueryMsg::RequestIds {} => to_binary(&self.request_ids(deps)?),
+            QueryMsg::Prompt {} => to_json_binary(&self.prompt_info(deps)?),
+            QueryMsg::RequestIds { start_after, limit } => {
+                to_json_binary(&self.request_ids(deps, start_after, limit)?)
+            }
+            QueryMsg::Request { token_id } => to_json_binary(&self.request(deps, token_id)?),
+            QueryMsg::AigcData { token_id } => to_json_binary(&self.aigc_data(deps, token_id)?),
+            QueryMsg::RequestTimeout {} => to_json_binary(&self.request_timeout(deps)?),
+            QueryMsg::Models { start_after, limit } => {
+                to_json_binary(&self.models(deps, start_after, limit)?)
+            }
+            QueryMsg::Generation { token_id } => to_json_binary(&self.generation(deps, token_id)?),
+            QueryMsg::PromptVersion { version } => {
+                to_json_binary(&self.prompt_version(deps, version)?)
+            }
+            QueryMsg::PromptVersions { start_after, limit } => {
+                to_json_binary(&self.prompt_versions(deps, start_after, limit)?)
+            }
+            QueryMsg::PromptTemplate {} => to_json_binary(&self.prompt_template(deps)?),
+            QueryMsg::RegisteredPrompt { prompt_id } => {
+                to_json_binary(&self.registered_prompt(deps, prompt_id)?)
+            }
+            QueryMsg::RegisteredPrompts { start_after, limit } => {
+                to_json_binary(&self.registered_prompts(deps, start_after, limit)?)
+            }
+            QueryMsg::TokensByPrompt {
+                prompt_id,
+                start_after,
+                limit,
+            } => to_json_binary(&self.tokens_by_prompt(deps, prompt_id, start_after, limit)?),
+            QueryMsg::TokenIdStrategy {} => to_json_binary(&self.token_id_strategy(deps)?),
+            QueryMsg::MintFee {} => to_json_binary(&self.mint_fee(deps)?),
+            QueryMsg::Verifier {} => to_json_binary(&self.verifier(deps)?),
+            QueryMsg::Verification { token_id } => {
+                to_json_binary(&self.verification(deps, token_id)?)
+            }
+            QueryMsg::Optimistic {} => to_json_binary(&self.optimistic(deps)?),
+            QueryMsg::Proposal { token_id } => to_json_binary(&self.proposal(deps, token_id)?),
+            QueryMsg::VerifyingKey {} => to_json_binary(&self.verifying_key(deps)?),
+            QueryMsg::Staking {} => to_json_binary(&self.staking(deps)?),
+            QueryMsg::Stake { oracle } => to_json_binary(&self.stake(deps, oracle)?),
+            QueryMsg::Assignment {} => to_json_binary(&self.assignment(deps)?),
+            QueryMsg::AssignedRequests {
+                oracle,
+                start_after,
+                limit,
+            } => to_json_binary(&self.assigned_requests(deps, oracle, start_after, limit)?),
+            QueryMsg::Quorum {} => to_json_binary(&self.quorum(deps)?),
+            QueryMsg::Votes { token_id } => to_json_binary(&self.votes(deps, token_id)?),
+            QueryMsg::Reputation { oracle } => to_json_binary(&self.reputation(deps, oracle)?),
+            QueryMsg::RevealWindow {} => to_json_binary(&self.reveal_window(deps)?),
+            QueryMsg::Commitments { token_id } => {
+                to_json_binary(&self.commitments(deps, token_id)?)
+            }
+            QueryMsg::Oracle { address } => to_json_binary(&self.oracle(deps, address)?),
+            QueryMsg::Oracles { start_after, limit } => {
+                to_json_binary(&self.oracles(deps, start_after, limit)?)
+            }
+            QueryMsg::Cw20Prices { start_after, limit } => {
+                to_json_binary(&self.cw20_prices(deps, start_after, limit)?)
+            }
+            QueryMsg::Hooks {} => to_json_binary(&self.hooks(deps)?),
             _ => self.cw721.query(deps, env, msg.into()),
         }
     }
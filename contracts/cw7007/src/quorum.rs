@@ -0,0 +1,156 @@
+use cosmwasm_std::{
+    CustomMsg, DepsMut, Env, HexBinary, MessageInfo, Order, Response, StdError, StdResult,
+};
+use cw721_base::ContractError;
+use sha2::{Digest, Sha256};
+
+use crate::msg::{Generation, QuorumConfig, VerifierConfig};
+use crate::state::{Cw7007Contract, Extension};
+
+/// Make sure quorum mode is configured consistently before storing it.
+/// `exclusive_modes` tells whether optimistic or assignment mode is enabled
+pub fn validate_quorum(
+    config: &QuorumConfig,
+    verifier: Option<&VerifierConfig>,
+    exclusive_modes: bool,
+) -> StdResult<()> {
+    if config.threshold == 0 {
+        return Err(StdError::generic_err("quorum threshold must not be zero."));
+    }
+    // agreed outputs are revealed without attestation
+    if verifier == Some(&VerifierConfig::Oracle {}) {
+        return Err(StdError::generic_err(
+            "quorum mode can not be combined with the oracle verifier.",
+        ));
+    }
+    if exclusive_modes {
+        return Err(StdError::generic_err(
+            "quorum mode can not be combined with optimistic or assignment mode.",
+        ));
+    }
+    Ok(())
+}
+
+impl<'a, C, E, Q> Cw7007Contract<'a, Extension, C, E, Q>
+where
+    C: CustomMsg,
+    E: CustomMsg,
+    Q: CustomMsg,
+{
+    pub fn submit_output_hash(
+        &self,
+        deps: DepsMut,
+        env: Env,
+        info: MessageInfo,
+        token_id: String,
+        output_hash: HexBinary,
+    ) -> Result<Response<C>, ContractError> {
+        let config = self
+            .quorum
+            .load(deps.storage)?
+            .ok_or_else(|| StdError::generic_err("quorum mode is not enabled."))?;
+        self.assert_oracle(deps.storage, &info.sender)?;
+        self.load_open_request(deps.storage, &env, &token_id)?;
+
+        if output_hash.len() != 32 {
+            return Err(ContractError::Std(StdError::generic_err(
+                "output hash must be a sha256 hash.",
+            )));
+        }
+        if self.agreed_outputs.has(deps.storage, &token_id) {
+            return Err(ContractError::Std(StdError::generic_err(
+                "quorum is already reached.",
+            )));
+        }
+        if self.votes.has(deps.storage, (&token_id, &info.sender)) {
+            return Err(ContractError::Std(StdError::generic_err(
+                "output hash is already submitted.",
+            )));
+        }
+        self.votes
+            .save(deps.storage, (&token_id, &info.sender), &output_hash)?;
+
+        let votes = self
+            .votes
+            .prefix(&token_id)
+            .range(deps.storage, None, None, Order::Ascending)
+            .collect::<StdResult<Vec<_>>>()?;
+        let agreeing = votes
+            .iter()
+            .filter(|(_, hash)| *hash == output_hash)
+            .count();
+        let reached = agreeing >= config.threshold as usize;
+
+        // once agreed, every vote counts for or against the reputation of its oracle
+        if reached {
+            self.agreed_outputs
+                .save(deps.storage, &token_id, &output_hash)?;
+            for (oracle, hash) in &votes {
+                let mut reputation = self
+                    .reputations
+                    .may_load(deps.storage, oracle)?
+                    .unwrap_or_default();
+                if *hash == output_hash {
+                    reputation.agreed += 1;
+                } else {
+                    reputation.disagreed += 1;
+                }
+                self.reputations.save(deps.storage, oracle, &reputation)?;
+            }
+        }
+
+        Ok(Response::new()
+            .add_attribute("action", "submit_output_hash")
+            .add_attribute("sender", info.sender)
+            .add_attribute("token_id", token_id)
+            .add_attribute("output_hash", output_hash.to_hex())
+            .add_attribute("quorum_reached", reached.to_string()))
+    }
+
+    pub fn reveal_output(
+        &self,
+        deps: DepsMut,
+        env: Env,
+        info: MessageInfo,
+        token_id: String,
+        output: String,
+        generation: Option<Generation>,
+    ) -> Result<Response<C>, ContractError> {
+        let agreed = self
+            .agreed_outputs
+            .may_load(deps.storage, &token_id)?
+            .ok_or_else(|| StdError::generic_err("quorum is not reached yet."))?;
+        if self
+            .votes
+            .may_load(deps.storage, (&token_id, &info.sender))?
+            != Some(agreed.clone())
+        {
+            return Err(ContractError::Std(StdError::generic_err(
+                "only an agreeing oracle can reveal the output.",
+            )));
+        }
+        if Sha256::digest(output.as_bytes()).as_slice() != agreed.as_slice() {
+            return Err(ContractError::Std(StdError::generic_err(
+                "output does not match the agreed hash.",
+            )));
+        }
+        self.load_open_request(deps.storage, &env, &token_id)?;
+        self.check_generation(deps.storage, &token_id, generation.as_ref())?;
+
+        let res = self.finalize_response(
+            deps,
+            &env,
+            &info.sender,
+            &token_id,
+            &output,
+            None,
+            generation,
+        )?;
+
+        Ok(res
+            .add_attribute("action", "reveal_output")
+            .add_attribute("sender", info.sender)
+            .add_attribute("token_id", token_id)
+            .add_attribute("output", output))
+    }
+}
@@ -0,0 +1,223 @@
+use cosmwasm_std::{
+    coins, Addr, BankMsg, CustomMsg, Decimal, DepsMut, Env, MessageInfo, Response, StdError,
+    StdResult, Storage, Uint128,
+};
+use cw721_base::ContractError;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+use crate::msg::{OracleMetadata, StakingConfig};
+use crate::state::{Cw7007Contract, Oracle, Unbonding};
+
+/// Make sure a staking config is consistent before storing it
+pub fn validate_staking(config: &StakingConfig) -> StdResult<()> {
+    if config.denom.is_empty() {
+        return Err(StdError::generic_err("staking denom must not be empty."));
+    }
+    if config.dispute_slash > Decimal::one() {
+        return Err(StdError::generic_err("dispute slash must not exceed 1."));
+    }
+    Ok(())
+}
+
+impl<'a, T, C, E, Q> Cw7007Contract<'a, T, C, E, Q>
+where
+    T: Serialize + DeserializeOwned + Clone,
+    C: CustomMsg,
+    E: CustomMsg,
+    Q: CustomMsg,
+{
+    pub fn bond(
+        &self,
+        deps: DepsMut,
+        info: MessageInfo,
+        metadata: Option<OracleMetadata>,
+    ) -> Result<Response<C>, ContractError> {
+        let config = self.load_staking(deps.storage)?;
+        let amount = cw_utils::must_pay(&info, &config.denom)
+            .map_err(|err| StdError::generic_err(err.to_string()))?;
+
+        // only the owner can bring back an oracle it removed
+        if self.removed_oracles.has(deps.storage, &info.sender) {
+            return Err(ContractError::Std(StdError::generic_err(
+                "oracle was removed by the owner.",
+            )));
+        }
+        let oracle = match (self.oracles.may_load(deps.storage, &info.sender)?, metadata) {
+            (Some(oracle), Some(metadata)) => Oracle { metadata, ..oracle },
+            (Some(oracle), None) => oracle,
+            (None, Some(metadata)) => Oracle {
+                metadata,
+                active: true,
+            },
+            (None, None) => {
+                return Err(ContractError::Std(StdError::generic_err(
+                    "metadata is required to register.",
+                )));
+            }
+        };
+        self.oracles.save(deps.storage, &info.sender, &oracle)?;
+        self.set_assignable(deps.storage, &info.sender, oracle.active)?;
+
+        let mut stake = self
+            .stakes
+            .may_load(deps.storage, &info.sender)?
+            .unwrap_or_default();
+        stake.bonded += amount;
+        self.stakes.save(deps.storage, &info.sender, &stake)?;
+        self.update_assignment_weight(deps.storage, &info.sender)?;
+
+        Ok(Response::new()
+            .add_attribute("action", "bond")
+            .add_attribute("sender", info.sender)
+            .add_attribute("amount", amount)
+            .add_attribute("bonded", stake.bonded))
+    }
+
+    pub fn unbond(
+        &self,
+        deps: DepsMut,
+        env: Env,
+        info: MessageInfo,
+        amount: Uint128,
+    ) -> Result<Response<C>, ContractError> {
+        let config = self.load_staking(deps.storage)?;
+        let mut stake = self
+            .stakes
+            .may_load(deps.storage, &info.sender)?
+            .unwrap_or_default();
+        if amount.is_zero() || amount > stake.bonded {
+            return Err(ContractError::Std(StdError::generic_err(format!(
+                "can unbond up to {}{}.",
+                stake.bonded, config.denom
+            ))));
+        }
+
+        let release_at = config.unbonding_period.after(&env.block);
+        stake.bonded -= amount;
+        stake.unbonding.push(Unbonding { amount, release_at });
+        self.stakes.save(deps.storage, &info.sender, &stake)?;
+        self.update_assignment_weight(deps.storage, &info.sender)?;
+
+        Ok(Response::new()
+            .add_attribute("action", "unbond")
+            .add_attribute("sender", info.sender)
+            .add_attribute("amount", amount)
+            .add_attribute("release_at", release_at.to_string()))
+    }
+
+    pub fn claim(
+        &self,
+        deps: DepsMut,
+        env: Env,
+        info: MessageInfo,
+    ) -> Result<Response<C>, ContractError> {
+        let config = self.load_staking(deps.storage)?;
+        let mut stake = self
+            .stakes
+            .may_load(deps.storage, &info.sender)?
+            .unwrap_or_default();
+        let released = stake.release(&env.block);
+        if released.is_zero() {
+            return Err(ContractError::Std(StdError::generic_err(
+                "nothing to claim.",
+            )));
+        }
+        self.stakes.save(deps.storage, &info.sender, &stake)?;
+
+        Ok(Response::new()
+            .add_message(BankMsg::Send {
+                to_address: info.sender.to_string(),
+                amount: coins(released.u128(), config.denom),
+            })
+            .add_attribute("action", "claim")
+            .add_attribute("sender", info.sender)
+            .add_attribute("amount", released))
+    }
+
+    pub fn slash(
+        &self,
+        deps: DepsMut,
+        info: MessageInfo,
+        oracle: String,
+        amount: Uint128,
+    ) -> Result<Response<C>, ContractError> {
+        cw_ownable::assert_owner(deps.storage, &info.sender)?;
+        let config = self.load_staking(deps.storage)?;
+
+        let oracle = deps.api.addr_validate(&oracle)?;
+        let slashed = self.slash_stake(deps.storage, &oracle, amount)?;
+        if slashed.is_zero() {
+            return Err(ContractError::Std(StdError::generic_err(
+                "oracle has no stake.",
+            )));
+        }
+
+        Ok(Response::new()
+            .add_message(BankMsg::Burn {
+                amount: coins(slashed.u128(), config.denom),
+            })
+            .add_attribute("action", "slash")
+            .add_attribute("sender", info.sender)
+            .add_attribute("oracle", oracle)
+            .add_attribute("amount", slashed))
+    }
+
+    /// Slash the `dispute_slash` part of the stake of `oracle`, paying it to `challenger`.
+    /// Nothing to do when staking is disabled
+    pub fn slash_for_dispute(
+        &self,
+        storage: &mut dyn Storage,
+        oracle: &Addr,
+        challenger: &Addr,
+    ) -> StdResult<Vec<BankMsg>> {
+        let config = match self.staking.load(storage)? {
+            Some(config) => config,
+            None => return Ok(vec![]),
+        };
+        let total = self
+            .stakes
+            .may_load(storage, oracle)?
+            .unwrap_or_default()
+            .total();
+        let slashed = self.slash_stake(storage, oracle, total.mul_floor(config.dispute_slash))?;
+        if slashed.is_zero() {
+            return Ok(vec![]);
+        }
+
+        Ok(vec![BankMsg::Send {
+            to_address: challenger.to_string(),
+            amount: coins(slashed.u128(), config.denom),
+        }])
+    }
+
+    /// Whether `oracle` has enough stake bonded to respond. Always true when staking is disabled
+    pub fn has_min_stake(&self, storage: &dyn Storage, oracle: &Addr) -> StdResult<bool> {
+        Ok(match self.staking.load(storage)? {
+            Some(config) => {
+                let stake = self.stakes.may_load(storage, oracle)?.unwrap_or_default();
+                stake.bonded >= config.min_stake
+            }
+            None => true,
+        })
+    }
+
+    fn slash_stake(
+        &self,
+        storage: &mut dyn Storage,
+        oracle: &Addr,
+        amount: Uint128,
+    ) -> StdResult<Uint128> {
+        let mut stake = self.stakes.may_load(storage, oracle)?.unwrap_or_default();
+        let slashed = stake.slash(amount);
+        self.stakes.save(storage, oracle, &stake)?;
+        self.update_assignment_weight(storage, oracle)?;
+        Ok(slashed)
+    }
+
+    fn load_staking(&self, storage: &dyn Storage) -> Result<StakingConfig, ContractError> {
+        self.staking
+            .load(storage)?
+            .ok_or_else(|| StdError::generic_err("staking is not enabled.").into())
+    }
+}
@@ -1,14 +1,25 @@
 use cosmwasm_schema::cw_serde;
-use cosmwasm_std::CustomMsg;
-use cw_storage_plus::Item;
+use cosmwasm_std::{
+    to_json_binary, Addr, BankMsg, Binary, BlockInfo, Coin, CosmosMsg, CustomMsg, Decimal, Empty,
+    Env, HexBinary, StdError, StdResult, Timestamp, Uint128, WasmMsg,
+};
+use cw20::Cw20ExecuteMsg;
+use cw_controllers::Hooks;
+use cw_storage_plus::{Index, IndexList, IndexedMap, Item, Map, MultiIndex};
+use cw_utils::{Duration, Expiration};
 
 use serde::de::DeserializeOwned;
 use serde::Serialize;
 
 use cw721_base::Cw721Contract;
 
-use crate::msg::{PromptInfoResponse, RequestIdsResponse};
-// use crate::traits::Cw7007;
+use crate::model::generation_seed;
+use crate::msg::{
+    AssignmentConfig, Attestation, Callback, Generation, MintFeeConfig, OptimisticConfig,
+    OracleMetadata, PromptInfoResponse, PromptTemplate, QuorumConfig, StakingConfig,
+    TokenIdStrategy, VerificationStatus, VerifierConfig,
+};
+use crate::traits::Cw7007;
 
 #[cw_serde]
 pub struct Trait {
@@ -24,8 +35,8 @@ pub struct Metadata {
     pub image: Option<String>, // response in svg
     pub image_data: Option<String>,
     pub external_url: Option<String>,
-    pub description: Option<String>, // query from user
-    pub name: Option<String>,        // name
+    pub description: Option<String>,
+    pub name: Option<String>, // name
     pub attributes: Option<Vec<Trait>>,
     pub background_color: Option<String>,
     pub animation_url: Option<String>,
@@ -34,6 +45,414 @@ pub struct Metadata {
 
 pub type Extension = Option<Metadata>;
 
+/// Generation request opened by a mint
+#[cw_serde]
+pub struct Request {
+    pub token_id: String,
+    pub requester: Addr,
+    pub status: RequestStatus,
+    /// Block height of the mint
+    pub created_at: u64,
+    /// Every status the request went through, starting with `Pending` at mint
+    pub transitions: Vec<Transition>,
+    /// Deadline after which the request can be expired
+    pub expires: Option<Expiration>,
+    /// Funds paid at mint, held until the request closes
+    pub escrow: Escrow,
+    /// Part of the escrow owed to the treasury, as the fee config was at mint
+    pub treasury_cut: Option<TreasuryCut>,
+    /// Only oracle allowed to respond, any active oracle if unset
+    pub assignee: Option<Addr>,
+    /// Deadline of the assignee, after which the request can be reassigned
+    pub reassign_at: Option<Expiration>,
+    /// Contract to notify once the output is final
+    pub callback: Option<Callback>,
+    /// Seed oracles have to sample with, so that honest outputs can be reproduced
+    pub seed: u64,
+}
+
+impl Request {
+    pub fn new(token_id: String, requester: Addr, env: &Env) -> Self {
+        let block = &env.block;
+        Request {
+            seed: generation_seed(env, &token_id),
+            token_id,
+            requester,
+            status: RequestStatus::Pending,
+            created_at: block.height,
+            transitions: vec![Transition {
+                status: RequestStatus::Pending,
+                height: block.height,
+                time: block.time,
+            }],
+            expires: None,
+            escrow: Escrow::Native { coins: vec![] },
+            treasury_cut: None,
+            assignee: None,
+            reassign_at: None,
+            callback: None,
+        }
+    }
+
+    pub fn is_expired(&self, block: &BlockInfo) -> bool {
+        self.expires
+            .map(|expires| expires.is_expired(block))
+            .unwrap_or(false)
+    }
+
+    /// Make sure `oracle` may serve the request, open to every oracle when unassigned
+    pub fn assert_assignee(&self, oracle: &Addr) -> StdResult<()> {
+        match &self.assignee {
+            Some(assignee) if assignee != oracle => Err(StdError::generic_err(
+                "request is assigned to another oracle.",
+            )),
+            _ => Ok(()),
+        }
+    }
+
+    /// Give the escrow back to the requester
+    pub fn refund_msg<C>(&self) -> StdResult<Option<CosmosMsg<C>>> {
+        self.escrow.send_msg(&self.requester)
+    }
+
+    /// Move a pending request to one of the final statuses
+    pub fn transition(&mut self, status: RequestStatus, block: &BlockInfo) -> StdResult<()> {
+        if self.status != RequestStatus::Pending || status == RequestStatus::Pending {
+            return Err(StdError::generic_err(format!(
+                "request cannot go from {} to {}.",
+                self.status.key(),
+                status.key()
+            )));
+        }
+        self.status = status.clone();
+        self.transitions.push(Transition {
+            status,
+            height: block.height,
+            time: block.time,
+        });
+        Ok(())
+    }
+}
+
+/// ERC-7007 record of a token: the prompt it was minted for and the generated content
+#[cw_serde]
+pub struct AigcData {
+    /// User prompt, fixed at mint
+    pub prompt: String,
+    /// Version of the collection prompt the token was minted under
+    pub prompt_version: u32,
+    /// Registered prompt the token was minted under, instead of the collection prompt
+    pub prompt_id: Option<String>,
+    /// Final output of the oracles, unset until the request is fulfilled
+    pub aigc_data: Option<String>,
+    /// Model and parameters the output was generated with
+    pub generation: Option<Generation>,
+    /// Proof the token was verified with
+    pub proof: Option<String>,
+    pub minted_at: Timestamp,
+    pub fulfilled_at: Option<Timestamp>,
+    pub verified_at: Option<Timestamp>,
+}
+
+impl AigcData {
+    pub fn new(prompt: String, prompt_version: u32, block: &BlockInfo) -> Self {
+        AigcData {
+            prompt,
+            prompt_version,
+            prompt_id: None,
+            aigc_data: None,
+            generation: None,
+            proof: None,
+            minted_at: block.time,
+            fulfilled_at: None,
+            verified_at: None,
+        }
+    }
+}
+
+/// Collection prompt as published at some point, kept for the tokens minted under it
+#[cw_serde]
+pub struct PromptVersion {
+    pub version: u32,
+    pub prompt: String,
+    pub created_at: Timestamp,
+}
+
+/// Prompt of the registry, with its own tokens and price
+#[cw_serde]
+pub struct RegisteredPrompt {
+    pub id: String,
+    pub name: String,
+    pub prompt: String,
+    /// Native price of a mint, the collection price if unset
+    pub price: Option<Vec<Coin>>,
+    /// Cleared once the prompt is closed to new mints
+    pub open: bool,
+    pub created_at: Timestamp,
+}
+
+/// Treasury and its share of a fulfilled request's escrow
+#[cw_serde]
+pub struct TreasuryCut {
+    pub treasury: Addr,
+    pub share: Decimal,
+}
+
+/// Funds held for a request, either native coins or a CW20 amount
+#[cw_serde]
+pub enum Escrow {
+    Native { coins: Vec<Coin> },
+    Cw20 { token: Addr, amount: Uint128 },
+}
+
+impl Escrow {
+    /// Split off `share` of the escrow (rounded down), returning (share, rest)
+    pub fn split(&self, share: Decimal) -> (Escrow, Escrow) {
+        match self {
+            Escrow::Native { coins } => {
+                let (mut cut, mut rest) = (vec![], vec![]);
+                for coin in coins {
+                    let amount = coin.amount.mul_floor(share);
+                    cut.push(Coin::new(amount.u128(), &coin.denom));
+                    rest.push(Coin::new((coin.amount - amount).u128(), &coin.denom));
+                }
+                (
+                    Escrow::Native { coins: cut },
+                    Escrow::Native { coins: rest },
+                )
+            }
+            Escrow::Cw20 { token, amount } => {
+                let cut = amount.mul_floor(share);
+                (
+                    Escrow::Cw20 {
+                        token: token.clone(),
+                        amount: cut,
+                    },
+                    Escrow::Cw20 {
+                        token: token.clone(),
+                        amount: *amount - cut,
+                    },
+                )
+            }
+        }
+    }
+
+    /// Transfer the escrow to `recipient`, if there is anything to transfer
+    pub fn send_msg<C>(&self, recipient: &Addr) -> StdResult<Option<CosmosMsg<C>>> {
+        let msg = match self {
+            Escrow::Native { coins } => {
+                let amount: Vec<Coin> = coins
+                    .iter()
+                    .filter(|coin| !coin.amount.is_zero())
+                    .cloned()
+                    .collect();
+                if amount.is_empty() {
+                    return Ok(None);
+                }
+                BankMsg::Send {
+                    to_address: recipient.to_string(),
+                    amount,
+                }
+                .into()
+            }
+            Escrow::Cw20 { token, amount } => {
+                if amount.is_zero() {
+                    return Ok(None);
+                }
+                WasmMsg::Execute {
+                    contract_addr: token.to_string(),
+                    msg: to_json_binary(&Cw20ExecuteMsg::Transfer {
+                        recipient: recipient.to_string(),
+                        amount: *amount,
+                    })?,
+                    funds: vec![],
+                }
+                .into()
+            }
+        };
+        Ok(Some(msg))
+    }
+}
+
+/// Lifecycle of a request: `Pending` until it reaches one of the other, final, statuses
+#[cw_serde]
+pub enum RequestStatus {
+    /// Waiting for the oracle
+    Pending,
+    /// The output is written to the token
+    Fulfilled,
+    /// The oracle gave up
+    Failed { reason: String },
+    /// Nobody answered in time
+    Expired,
+    /// Withdrawn by the requester
+    Cancelled,
+}
+
+impl RequestStatus {
+    /// Key of the status index
+    pub fn key(&self) -> String {
+        match self {
+            RequestStatus::Pending => "pending",
+            RequestStatus::Fulfilled => "fulfilled",
+            RequestStatus::Failed { .. } => "failed",
+            RequestStatus::Expired => "expired",
+            RequestStatus::Cancelled => "cancelled",
+        }
+        .to_string()
+    }
+}
+
+#[cw_serde]
+pub struct Transition {
+    pub status: RequestStatus,
+    pub height: u64,
+    pub time: Timestamp,
+}
+
+pub struct RequestIndexes<'a> {
+    pub status: MultiIndex<'a, String, Request, String>,
+    pub requester: MultiIndex<'a, Addr, Request, String>,
+    pub created_at: MultiIndex<'a, u64, Request, String>,
+    /// (assignee address, status), the address is empty for unassigned requests.
+    /// Keyed by status too, so that closed requests don't pile up under their oracle
+    pub assignee_status: MultiIndex<'a, (String, String), Request, String>,
+}
+
+impl<'a> IndexList<Request> for RequestIndexes<'a> {
+    fn get_indexes(&'_ self) -> Box<dyn Iterator<Item = &'_ dyn Index<Request>> + '_> {
+        let v: Vec<&dyn Index<Request>> = vec![
+            &self.status,
+            &self.requester,
+            &self.created_at,
+            &self.assignee_status,
+        ];
+        Box::new(v.into_iter())
+    }
+}
+
+/// Output waiting for its challenge window to pass
+#[cw_serde]
+pub struct Proposal {
+    pub output: String,
+    pub attestation: Option<Attestation>,
+    pub generation: Option<Generation>,
+    pub proposer: Addr,
+    pub proposed_at: u64,
+    /// Last block height at which the proposal can be challenged
+    pub challenge_deadline: u64,
+    pub challenge: Option<Challenge>,
+}
+
+#[cw_serde]
+pub struct Challenge {
+    pub challenger: Addr,
+    pub bond: Coin,
+    pub challenged_at: u64,
+}
+
+/// Native stake of an oracle
+#[cw_serde]
+#[derive(Default)]
+pub struct Stake {
+    pub bonded: Uint128,
+    /// Unbonded amounts waiting for the unbonding period, still slashable
+    pub unbonding: Vec<Unbonding>,
+}
+
+/// Eligible oracles and their bonded stake, over a range of assignable positions
+#[cw_serde]
+#[derive(Default, Copy)]
+pub struct AssignmentWeight {
+    pub oracles: u32,
+    pub stake: Uint128,
+}
+
+impl AssignmentWeight {
+    pub fn plus(&self, other: &AssignmentWeight) -> AssignmentWeight {
+        AssignmentWeight {
+            oracles: self.oracles + other.oracles,
+            stake: self.stake + other.stake,
+        }
+    }
+
+    pub fn minus(&self, other: &AssignmentWeight) -> AssignmentWeight {
+        AssignmentWeight {
+            oracles: self.oracles - other.oracles,
+            stake: self.stake - other.stake,
+        }
+    }
+}
+
+#[cw_serde]
+pub struct Unbonding {
+    pub amount: Uint128,
+    pub release_at: Expiration,
+}
+
+impl Stake {
+    pub fn total(&self) -> Uint128 {
+        self.unbonding
+            .iter()
+            .fold(self.bonded, |total, unbonding| total + unbonding.amount)
+    }
+
+    /// Take up to `amount` out of the stake, bonded first then the latest unbondings.
+    /// Returns the amount actually taken
+    pub fn slash(&mut self, amount: Uint128) -> Uint128 {
+        let mut left = amount;
+        let taken = left.min(self.bonded);
+        self.bonded -= taken;
+        left -= taken;
+        for unbonding in self.unbonding.iter_mut().rev() {
+            let taken = left.min(unbonding.amount);
+            unbonding.amount -= taken;
+            left -= taken;
+        }
+        self.unbonding
+            .retain(|unbonding| !unbonding.amount.is_zero());
+        amount - left
+    }
+
+    /// Remove the unbondings released at `block`, returning their total
+    pub fn release(&mut self, block: &BlockInfo) -> Uint128 {
+        let (released, unbonding) = self
+            .unbonding
+            .drain(..)
+            .partition::<Vec<_>, _>(|unbonding| unbonding.release_at.is_expired(block));
+        self.unbonding = unbonding;
+        released
+            .iter()
+            .fold(Uint128::zero(), |total, unbonding| total + unbonding.amount)
+    }
+}
+
+/// Hidden output posted ahead of its reveal
+#[cw_serde]
+pub struct Commitment {
+    pub hash: HexBinary,
+    /// Block height of the commit
+    pub committed_at: u64,
+    /// Deadline of the reveal, after which the commitment is void
+    pub reveal_by: Expiration,
+}
+
+/// How an oracle's output hashes compared to the quorum
+#[cw_serde]
+#[derive(Default)]
+pub struct Reputation {
+    pub agreed: u64,
+    pub disagreed: u64,
+}
+
+/// Registered oracle, allowed to post outputs while active
+#[cw_serde]
+pub struct Oracle {
+    pub metadata: OracleMetadata,
+    /// Cleared when the owner pauses the oracle
+    pub active: bool,
+}
+
 pub struct Cw7007Contract<'a, T, C, E, Q>
 where
     T: Serialize + DeserializeOwned + Clone,
@@ -43,7 +462,73 @@ where
     pub cw721: cw721_base::Cw721Contract<'a, T, C, E, Q>,
 
     pub prompt_info: Item<'a, PromptInfoResponse>,
-    pub request_ids: Item<'a, RequestIdsResponse>,
+    pub prompt_versions: Map<'a, u32, PromptVersion>,
+    pub prompts: Map<'a, &'a str, RegisteredPrompt>,
+    /// Tokens of every registered prompt, keyed by (prompt id, token id)
+    pub prompt_tokens: Map<'a, (&'a str, &'a str), Empty>,
+    pub requests: IndexedMap<'a, &'a str, Request, RequestIndexes<'a>>,
+    pub aigc_data: Map<'a, &'a str, AigcData>,
+    pub prompt_template: Item<'a, Option<PromptTemplate>>,
+    /// Weights hash of every allowed model
+    pub models: Map<'a, &'a str, HexBinary>,
+    pub token_id_strategy: Item<'a, TokenIdStrategy>,
+    /// Next id of the `Counter` strategy
+    pub token_id_counter: Item<'a, u64>,
+    /// Last token converted by a migration that takes several calls
+    pub migration_cursor: Item<'a, String>,
+
+    pub verifier: Item<'a, Option<VerifierConfig>>,
+    pub verifications: Map<'a, &'a str, VerificationStatus>,
+    pub verifying_key: Item<'a, Binary>,
+
+    pub oracles: Map<'a, &'a Addr, Oracle>,
+    /// Oracles removed by the owner, who can't register again by bonding
+    pub removed_oracles: Map<'a, &'a Addr, Empty>,
+    pub staking: Item<'a, Option<StakingConfig>>,
+    pub stakes: Map<'a, &'a Addr, Stake>,
+
+    pub assignment: Item<'a, Option<AssignmentConfig>>,
+    /// Registered oracles that are not paused, packed by position
+    /// so that assignment can pick one without scanning every oracle
+    pub assignable_oracles: Map<'a, u32, Addr>,
+    /// Position of every oracle in `assignable_oracles`
+    pub assignable_positions: Map<'a, &'a Addr, u32>,
+    pub assignable_count: Item<'a, u32>,
+    /// Fenwick tree of the weights of `assignable_oracles`, keyed by position + 1
+    pub assignment_tree: Map<'a, u32, AssignmentWeight>,
+    /// Position of the last oracle picked
+    pub assignment_cursor: Item<'a, u32>,
+
+    pub quorum: Item<'a, Option<QuorumConfig>>,
+    pub votes: Map<'a, (&'a str, &'a Addr), HexBinary>,
+    pub agreed_outputs: Map<'a, &'a str, HexBinary>,
+    pub reputations: Map<'a, &'a Addr, Reputation>,
+
+    pub reveal_window: Item<'a, Option<Duration>>,
+    pub commitments: Map<'a, (&'a str, &'a Addr), Commitment>,
+
+    /// Token of every callback in flight by reply id, read back in its reply.
+    /// A callback can finalize other requests, so several can be in flight at once
+    pub callback_tokens: Map<'a, u64, String>,
+    /// Reply ids handed out to callbacks so far
+    pub callback_count: Item<'a, u64>,
+    pub callback_gas_limit: Item<'a, u64>,
+    pub hooks: Hooks<'a>,
+
+    pub request_timeout: Item<'a, Option<Duration>>,
+    pub mint_fee: Item<'a, Option<MintFeeConfig>>,
+    pub cw20_prices: Map<'a, &'a Addr, Uint128>,
+
+    pub optimistic: Item<'a, Option<OptimisticConfig>>,
+    pub proposals: Map<'a, &'a str, Proposal>,
+}
+
+impl<'a, C, E, Q> Cw7007<Extension, C> for Cw7007Contract<'a, Extension, C, E, Q>
+where
+    C: CustomMsg,
+    E: CustomMsg,
+    Q: CustomMsg,
+{
 }
 
 impl<T, C, E, Q> Default for Cw7007Contract<'static, T, C, E, Q>
@@ -53,7 +538,7 @@ where
     Q: CustomMsg,
 {
     fn default() -> Self {
-        Self::new("prompt", "requestids")
+        Self::new("prompt", "requests")
     }
 }
 
@@ -63,10 +548,74 @@ where
     E: CustomMsg,
     Q: CustomMsg,
 {
-    fn new(prompt_info_key: &'a str, request_ids_key: &'a str) -> Self {
+    fn new(prompt_info_key: &'a str, requests_key: &'a str) -> Self {
+        let indexes = RequestIndexes {
+            status: MultiIndex::new(
+                |_, r: &Request| r.status.key(),
+                requests_key,
+                "requests__status",
+            ),
+            requester: MultiIndex::new(
+                |_, r: &Request| r.requester.clone(),
+                requests_key,
+                "requests__requester",
+            ),
+            created_at: MultiIndex::new(
+                |_, r: &Request| r.created_at,
+                requests_key,
+                "requests__created_at",
+            ),
+            assignee_status: MultiIndex::new(
+                |_, r: &Request| {
+                    (
+                        r.assignee.as_ref().map(Addr::to_string).unwrap_or_default(),
+                        r.status.key(),
+                    )
+                },
+                requests_key,
+                "requests__assignee_status",
+            ),
+        };
         Self {
             prompt_info: Item::new(prompt_info_key),
-            request_ids: Item::new(request_ids_key),
+            prompt_versions: Map::new("prompt_versions"),
+            prompts: Map::new("prompts"),
+            prompt_tokens: Map::new("prompt_tokens"),
+            requests: IndexedMap::new(requests_key, indexes),
+            aigc_data: Map::new("aigc_data"),
+            prompt_template: Item::new("prompt_template"),
+            models: Map::new("models"),
+            token_id_strategy: Item::new("token_id_strategy"),
+            token_id_counter: Item::new("token_id_counter"),
+            migration_cursor: Item::new("migration_cursor"),
+            verifier: Item::new("verifier"),
+            verifications: Map::new("verifications"),
+            verifying_key: Item::new("verifying_key"),
+            oracles: Map::new("oracles"),
+            removed_oracles: Map::new("removed_oracles"),
+            staking: Item::new("staking"),
+            stakes: Map::new("stakes"),
+            assignment: Item::new("assignment"),
+            assignable_oracles: Map::new("assignable_oracles"),
+            assignable_positions: Map::new("assignable_positions"),
+            assignable_count: Item::new("assignable_count"),
+            assignment_tree: Map::new("assignment_tree"),
+            assignment_cursor: Item::new("assignment_cursor"),
+            quorum: Item::new("quorum"),
+            votes: Map::new("votes"),
+            agreed_outputs: Map::new("agreed_outputs"),
+            reputations: Map::new("reputations"),
+            reveal_window: Item::new("reveal_window"),
+            commitments: Map::new("commitments"),
+            callback_tokens: Map::new("callback_tokens"),
+            callback_count: Item::new("callback_count"),
+            callback_gas_limit: Item::new("callback_gas_limit"),
+            hooks: Hooks::new("hooks"),
+            request_timeout: Item::new("request_timeout"),
+            mint_fee: Item::new("mint_fee"),
+            cw20_prices: Map::new("cw20_prices"),
+            optimistic: Item::new("optimistic"),
+            proposals: Map::new("proposals"),
             cw721: Cw721Contract::default(),
         }
     }
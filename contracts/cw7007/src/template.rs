@@ -0,0 +1,249 @@
+use cosmwasm_std::{CustomMsg, DepsMut, MessageInfo, Response, StdError, StdResult, Storage};
+use cw721_base::ContractError;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+use crate::msg::{Charset, PromptTemplate, PromptVariable, TemplateVariable};
+use crate::state::Cw7007Contract;
+
+/// Piece of a parsed template
+enum Segment<'t> {
+    Text(String),
+    Placeholder(&'t str),
+}
+
+/// Split a template into text and placeholders, unescaping `{{` and `}}`
+fn parse(template: &str) -> StdResult<Vec<Segment<'_>>> {
+    let mut segments = vec![];
+    let mut text = String::new();
+    let mut rest = template;
+    while let Some(i) = rest.find(['{', '}']) {
+        text.push_str(&rest[..i]);
+        let (brace, after) = (&rest[i..i + 1], &rest[i + 1..]);
+        if after.starts_with(brace) {
+            text.push_str(brace);
+            rest = &after[1..];
+        } else if brace == "{" {
+            let end = after
+                .find('}')
+                .ok_or_else(|| StdError::generic_err("template has an unclosed placeholder."))?;
+            segments.push(Segment::Text(std::mem::take(&mut text)));
+            segments.push(Segment::Placeholder(&after[..end]));
+            rest = &after[end + 1..];
+        } else {
+            return Err(StdError::generic_err(
+                "template has an unmatched '}', write '}}' instead.",
+            ));
+        }
+    }
+    text.push_str(rest);
+    segments.push(Segment::Text(text));
+    Ok(segments)
+}
+
+/// Tell whether `c` belongs to `charset`
+fn allows(charset: &Charset, c: char) -> bool {
+    match charset {
+        Charset::Alphabetic {} => c.is_ascii_alphabetic() || c == ' ',
+        Charset::Numeric {} => c.is_ascii_digit(),
+        Charset::Alphanumeric {} => c.is_ascii_alphanumeric() || c == ' ',
+        Charset::Printable {} => c.is_ascii_graphic() || c == ' ',
+    }
+}
+
+/// Check a value against the constraints of its variable
+fn check_value(variable: &TemplateVariable, value: &str) -> StdResult<()> {
+    let name = &variable.name;
+    if value.is_empty() {
+        return Err(StdError::generic_err(format!(
+            "variable {} must not be empty.",
+            name
+        )));
+    }
+    if value.chars().count() > variable.max_length as usize {
+        return Err(StdError::generic_err(format!(
+            "variable {} must not exceed {} characters.",
+            name, variable.max_length
+        )));
+    }
+    if let Some(charset) = &variable.charset {
+        if !value.chars().all(|c| allows(charset, c)) {
+            return Err(StdError::generic_err(format!(
+                "variable {} has characters out of its charset.",
+                name
+            )));
+        }
+    }
+    if let Some(choices) = &variable.choices {
+        if !choices.iter().any(|choice| choice == value) {
+            return Err(StdError::generic_err(format!(
+                "variable {} must be one of [{}].",
+                name,
+                choices.join(", ")
+            )));
+        }
+    }
+    Ok(())
+}
+
+/// Make sure every placeholder is declared once and used, and that choices fit their constraints
+pub fn validate_template(template: &PromptTemplate) -> StdResult<()> {
+    for (i, variable) in template.variables.iter().enumerate() {
+        let name = &variable.name;
+        if name.is_empty()
+            || !name
+                .chars()
+                .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_')
+        {
+            return Err(StdError::generic_err(format!(
+                "variable name '{}' must be lowercase ascii letters, digits or '_'.",
+                name
+            )));
+        }
+        if template.variables[..i].iter().any(|v| &v.name == name) {
+            return Err(StdError::generic_err(format!(
+                "variable {} is declared twice.",
+                name
+            )));
+        }
+        if variable.max_length == 0 {
+            return Err(StdError::generic_err(format!(
+                "variable {} must have a non zero max length.",
+                name
+            )));
+        }
+        if let Some(choices) = &variable.choices {
+            if choices.is_empty() {
+                return Err(StdError::generic_err(format!(
+                    "variable {} must have at least one choice.",
+                    name
+                )));
+            }
+            for choice in choices {
+                check_value(variable, choice)?;
+            }
+        }
+    }
+
+    let placeholders: Vec<&str> = parse(&template.template)?
+        .into_iter()
+        .filter_map(|segment| match segment {
+            Segment::Placeholder(name) => Some(name),
+            Segment::Text(_) => None,
+        })
+        .collect();
+    if let Some(name) = placeholders
+        .iter()
+        .find(|name| !template.variables.iter().any(|v| &v.name == *name))
+    {
+        return Err(StdError::generic_err(format!(
+            "placeholder {{{}}} is not declared.",
+            name
+        )));
+    }
+    if let Some(variable) = template
+        .variables
+        .iter()
+        .find(|v| !placeholders.contains(&v.name.as_str()))
+    {
+        return Err(StdError::generic_err(format!(
+            "variable {} is not used in the template.",
+            variable.name
+        )));
+    }
+    Ok(())
+}
+
+/// Fill the placeholders of a validated template in, after checking every value
+pub fn render(template: &PromptTemplate, values: &[PromptVariable]) -> StdResult<String> {
+    if let Some(value) = values
+        .iter()
+        .find(|value| !template.variables.iter().any(|v| v.name == value.name))
+    {
+        return Err(StdError::generic_err(format!(
+            "variable {} is not part of the template.",
+            value.name
+        )));
+    }
+    for variable in &template.variables {
+        let mut given = values.iter().filter(|value| value.name == variable.name);
+        match (given.next(), given.next()) {
+            (Some(value), None) => check_value(variable, &value.value)?,
+            (None, _) => {
+                return Err(StdError::generic_err(format!(
+                    "variable {} is missing.",
+                    variable.name
+                )))
+            }
+            (Some(_), Some(_)) => {
+                return Err(StdError::generic_err(format!(
+                    "variable {} is given twice.",
+                    variable.name
+                )))
+            }
+        }
+    }
+
+    Ok(parse(&template.template)?
+        .into_iter()
+        .map(|segment| match segment {
+            Segment::Text(text) => text,
+            Segment::Placeholder(name) => values
+                .iter()
+                .find(|value| value.name == name)
+                .map(|value| value.value.clone())
+                .unwrap_or_default(),
+        })
+        .collect())
+}
+
+impl<'a, T, C, E, Q> Cw7007Contract<'a, T, C, E, Q>
+where
+    T: Serialize + DeserializeOwned + Clone,
+    C: CustomMsg,
+    E: CustomMsg,
+    Q: CustomMsg,
+{
+    pub fn set_prompt_template(
+        &self,
+        deps: DepsMut,
+        info: MessageInfo,
+        template: Option<PromptTemplate>,
+    ) -> Result<Response<C>, ContractError> {
+        cw_ownable::assert_owner(deps.storage, &info.sender)?;
+
+        if let Some(template) = &template {
+            validate_template(template)?;
+        }
+        self.prompt_template.save(deps.storage, &template)?;
+
+        Ok(Response::new()
+            .add_attribute("action", "set_prompt_template")
+            .add_attribute("sender", info.sender))
+    }
+
+    /// Prompt of a new token: rendered from the template if there is one,
+    /// otherwise the one written by the minter
+    pub fn mint_prompt(
+        &self,
+        storage: &dyn Storage,
+        prompt: String,
+        variables: Option<Vec<PromptVariable>>,
+    ) -> StdResult<String> {
+        match (self.prompt_template.load(storage)?, variables) {
+            (Some(template), variables) if prompt.is_empty() => {
+                render(&template, &variables.unwrap_or_default())
+            }
+            (Some(_), _) => Err(StdError::generic_err(
+                "prompt is rendered from the template, only give its variables.",
+            )),
+            (None, Some(_)) => Err(StdError::generic_err(
+                "variables require a prompt template.",
+            )),
+            (None, None) if prompt.is_empty() => {
+                Err(StdError::generic_err("prompt must not be empty."))
+            }
+            (None, None) => Ok(prompt),
+        }
+    }
+}
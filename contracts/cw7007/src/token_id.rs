@@ -0,0 +1,81 @@
+use cosmwasm_std::{CustomMsg, HexBinary, StdError, StdResult, Storage};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+
+use crate::msg::TokenIdStrategy;
+use crate::state::Cw7007Contract;
+
+/// Longest id accepted from the caller
+const MAX_TOKEN_ID_LENGTH: usize = 64;
+
+/// Make sure an id given by the caller is short and safe to use in URLs and storage keys
+pub fn validate_token_id(token_id: &str) -> StdResult<()> {
+    if token_id.is_empty() || token_id.len() > MAX_TOKEN_ID_LENGTH {
+        return Err(StdError::generic_err(format!(
+            "token id must be 1 to {} characters long.",
+            MAX_TOKEN_ID_LENGTH
+        )));
+    }
+    if !token_id
+        .chars()
+        .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'))
+    {
+        return Err(StdError::generic_err(
+            "token id must only contain ascii letters, digits, '-', '_' or '.'.",
+        ));
+    }
+    Ok(())
+}
+
+/// ERC-7007 token id: the hex encoded sha256 of the prompt
+pub fn prompt_hash(prompt: &str) -> String {
+    HexBinary::from(Sha256::digest(prompt.as_bytes()).as_slice()).to_hex()
+}
+
+impl<'a, T, C, E, Q> Cw7007Contract<'a, T, C, E, Q>
+where
+    T: Serialize + DeserializeOwned + Clone,
+    C: CustomMsg,
+    E: CustomMsg,
+    Q: CustomMsg,
+{
+    /// Pick the id of a new token according to the token id strategy.
+    /// Ids are never reused, burned tokens included
+    pub fn new_token_id(
+        &self,
+        storage: &mut dyn Storage,
+        token_id: Option<String>,
+        prompt: &str,
+    ) -> StdResult<String> {
+        let token_id = match self.token_id_strategy.load(storage)? {
+            TokenIdStrategy::Counter {} => {
+                // contracts predating the counter never burned below their token count
+                let next = match self.token_id_counter.may_load(storage)? {
+                    Some(next) => next,
+                    None => self.cw721.token_count(storage)?,
+                };
+                self.token_id_counter.save(storage, &(next + 1))?;
+                next.to_string()
+            }
+            TokenIdStrategy::Caller {} => {
+                let token_id =
+                    token_id.ok_or_else(|| StdError::generic_err("token id is required."))?;
+                validate_token_id(&token_id)?;
+                token_id
+            }
+            TokenIdStrategy::PromptHash {} => {
+                let token_id = prompt_hash(prompt);
+                if self.aigc_data.has(storage, &token_id) {
+                    return Err(StdError::generic_err("prompt was already minted."));
+                }
+                token_id
+            }
+        };
+
+        if self.aigc_data.has(storage, &token_id) {
+            return Err(StdError::generic_err("token id is already taken."));
+        }
+        Ok(token_id)
+    }
+}
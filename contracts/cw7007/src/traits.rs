@@ -1,9 +1,21 @@
 use serde::de::DeserializeOwned;
 use serde::Serialize;
 
-use cosmwasm_std::{CustomMsg, Deps, DepsMut, Env, MessageInfo, Response, StdResult};
+use cosmwasm_std::{Binary, CustomMsg, Deps, DepsMut, Env, MessageInfo, Response, StdResult};
+use cw721::TokensResponse;
+use cw_controllers::HooksResponse;
+use cw_utils::Duration;
 
-use crate::msg::{PromptInfoResponse, RequestIdsResponse};
+use crate::msg::{
+    AssignmentConfig, Attestation, CommitmentsResponse, Cw20PricesResponse, Generation,
+    MintFeeConfig, ModelsResponse, OptimisticConfig, OraclesResponse, PromptInfoResponse,
+    PromptTemplate, PromptVersionsResponse, QuorumConfig, RegisteredPromptsResponse,
+    RequestIdsResponse, StakingConfig, TokenIdStrategy, VerificationResponse, VerifierConfig,
+    VotesResponse,
+};
+use crate::state::{
+    AigcData, Oracle, PromptVersion, Proposal, RegisteredPrompt, Reputation, Request, Stake,
+};
 
 pub trait Cw7007<T, C>: Cw7007Execute<T, C> + Cw7007Query<T>
 where
@@ -19,6 +31,7 @@ where
 {
     type Err: ToString;
 
+    #[allow(clippy::too_many_arguments)]
     fn response(
         &self,
         deps: DepsMut,
@@ -26,6 +39,8 @@ where
         info: MessageInfo,
         token_id: String,
         output: String,
+        attestation: Option<Attestation>,
+        generation: Option<Generation>,
     ) -> Result<Response<C>, Self::Err>;
 
     fn verify(
@@ -43,5 +58,78 @@ where
     T: Serialize + DeserializeOwned + Clone,
 {
     fn prompt_info(&self, deps: Deps) -> StdResult<PromptInfoResponse>;
-    fn request_ids(&self, deps: Deps) -> StdResult<RequestIdsResponse>;
+    fn request_ids(
+        &self,
+        deps: Deps,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    ) -> StdResult<RequestIdsResponse>;
+    fn request(&self, deps: Deps, token_id: String) -> StdResult<Request>;
+    fn aigc_data(&self, deps: Deps, token_id: String) -> StdResult<AigcData>;
+    fn request_timeout(&self, deps: Deps) -> StdResult<Option<Duration>>;
+    fn token_id_strategy(&self, deps: Deps) -> StdResult<TokenIdStrategy>;
+    fn prompt_template(&self, deps: Deps) -> StdResult<Option<PromptTemplate>>;
+    fn registered_prompt(&self, deps: Deps, prompt_id: String) -> StdResult<RegisteredPrompt>;
+    fn registered_prompts(
+        &self,
+        deps: Deps,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    ) -> StdResult<RegisteredPromptsResponse>;
+    fn tokens_by_prompt(
+        &self,
+        deps: Deps,
+        prompt_id: String,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    ) -> StdResult<TokensResponse>;
+    fn models(
+        &self,
+        deps: Deps,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    ) -> StdResult<ModelsResponse>;
+    fn generation(&self, deps: Deps, token_id: String) -> StdResult<Option<Generation>>;
+    fn prompt_version(&self, deps: Deps, version: u32) -> StdResult<PromptVersion>;
+    fn prompt_versions(
+        &self,
+        deps: Deps,
+        start_after: Option<u32>,
+        limit: Option<u32>,
+    ) -> StdResult<PromptVersionsResponse>;
+    fn mint_fee(&self, deps: Deps) -> StdResult<Option<MintFeeConfig>>;
+    fn verifier(&self, deps: Deps) -> StdResult<Option<VerifierConfig>>;
+    fn verification(&self, deps: Deps, token_id: String) -> StdResult<VerificationResponse>;
+    fn optimistic(&self, deps: Deps) -> StdResult<Option<OptimisticConfig>>;
+    fn proposal(&self, deps: Deps, token_id: String) -> StdResult<Option<Proposal>>;
+    fn verifying_key(&self, deps: Deps) -> StdResult<Option<Binary>>;
+    fn staking(&self, deps: Deps) -> StdResult<Option<StakingConfig>>;
+    fn stake(&self, deps: Deps, oracle: String) -> StdResult<Stake>;
+    fn assignment(&self, deps: Deps) -> StdResult<Option<AssignmentConfig>>;
+    fn assigned_requests(
+        &self,
+        deps: Deps,
+        oracle: String,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    ) -> StdResult<RequestIdsResponse>;
+    fn quorum(&self, deps: Deps) -> StdResult<Option<QuorumConfig>>;
+    fn votes(&self, deps: Deps, token_id: String) -> StdResult<VotesResponse>;
+    fn reputation(&self, deps: Deps, oracle: String) -> StdResult<Reputation>;
+    fn reveal_window(&self, deps: Deps) -> StdResult<Option<Duration>>;
+    fn commitments(&self, deps: Deps, token_id: String) -> StdResult<CommitmentsResponse>;
+    fn oracle(&self, deps: Deps, address: String) -> StdResult<Option<Oracle>>;
+    fn oracles(
+        &self,
+        deps: Deps,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    ) -> StdResult<OraclesResponse>;
+    fn cw20_prices(
+        &self,
+        deps: Deps,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    ) -> StdResult<Cw20PricesResponse>;
+    fn hooks(&self, deps: Deps) -> StdResult<HooksResponse>;
 }
@@ -0,0 +1,172 @@
+use ark_bn254::{Bn254, Fr};
+use ark_ff::PrimeField;
+use ark_groth16::{prepare_verifying_key, Groth16, Proof, VerifyingKey};
+use ark_serialize::CanonicalDeserialize;
+use cosmwasm_std::{from_json, Binary, CustomMsg, Deps, StdError, StdResult};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+
+use crate::msg::{
+    Attestation, SignatureScheme, VerifierConfig, VerifierQueryMsg, VerifyProofResponse,
+};
+use crate::state::Cw7007Contract;
+
+/// Domain separator mixed into every verification hash
+const DOMAIN: &[u8] = b"cw7007";
+
+/// Number of public inputs of a Groth16 circuit: (request hash, output hash)
+const GROTH16_PUBLIC_INPUTS: usize = 2;
+
+/// Everything a verifier backend is allowed to look at for one token
+pub struct VerificationInput {
+    /// Collection prompt
+    pub prompt: String,
+    /// User prompt given at mint
+    pub input: String,
+    /// Seed of the request, which the output has to be sampled with
+    pub seed: u64,
+    /// AI output (the token's aigc data)
+    pub output: String,
+}
+
+impl VerificationInput {
+    /// Canonical sha256 over (prompt, input, seed, output), the seed as a decimal string.
+    /// Every field is length-prefixed so that the concatenation is unambiguous.
+    pub fn hash(&self) -> [u8; 32] {
+        hash_fields(&[
+            &self.prompt,
+            &self.input,
+            &self.seed.to_string(),
+            &self.output,
+        ])
+    }
+
+    /// Groth16 public inputs: hash of (prompt, input, seed) and hash of output,
+    /// both reduced into the BN254 scalar field
+    pub fn public_inputs(&self) -> [Fr; GROTH16_PUBLIC_INPUTS] {
+        [
+            Fr::from_be_bytes_mod_order(&hash_fields(&[
+                &self.prompt,
+                &self.input,
+                &self.seed.to_string(),
+            ])),
+            Fr::from_be_bytes_mod_order(&hash_fields(&[&self.output])),
+        ]
+    }
+}
+
+/// Commitment an oracle posts before revealing `output`.
+/// Binding the oracle address keeps other oracles from replaying it
+pub fn commitment_hash(token_id: &str, output: &str, salt: &str, oracle: &str) -> [u8; 32] {
+    hash_fields(&[token_id, output, salt, oracle])
+}
+
+fn hash_fields(fields: &[&str]) -> [u8; 32] {
+    let mut hasher = Sha256::new();
+    hasher.update(DOMAIN);
+    for field in fields {
+        hasher.update((field.len() as u64).to_be_bytes());
+        hasher.update(field.as_bytes());
+    }
+    hasher.finalize().into()
+}
+
+/// Deserialize a compressed BN254 Groth16 verifying key, checking it fits our public inputs
+pub fn parse_verifying_key(vk: &[u8]) -> StdResult<VerifyingKey<Bn254>> {
+    let vk = VerifyingKey::<Bn254>::deserialize_compressed(vk)
+        .map_err(|err| StdError::parse_err("VerifyingKey", err.to_string()))?;
+    if vk.gamma_abc_g1.len() != GROTH16_PUBLIC_INPUTS + 1 {
+        return Err(StdError::generic_err(format!(
+            "verifying key must have {} public inputs.",
+            GROTH16_PUBLIC_INPUTS
+        )));
+    }
+    Ok(vk)
+}
+
+/// Check a compressed BN254 Groth16 proof against the public inputs derived from `input`
+pub fn verify_groth16(vk: &[u8], input: &VerificationInput, proof: &[u8]) -> StdResult<bool> {
+    let vk = parse_verifying_key(vk)?;
+    let proof = Proof::<Bn254>::deserialize_compressed(proof)
+        .map_err(|err| StdError::parse_err("Proof", err.to_string()))?;
+
+    Groth16::<Bn254>::verify_proof(&prepare_verifying_key(&vk), &proof, &input.public_inputs())
+        .map_err(|err| StdError::generic_err(err.to_string()))
+}
+
+impl<'a, T, C, E, Q> Cw7007Contract<'a, T, C, E, Q>
+where
+    T: Serialize + DeserializeOwned + Clone,
+    E: CustomMsg,
+    Q: CustomMsg,
+{
+    /// Tell whether `proof` ties the output to its prompt, according to `verifier`
+    pub fn verify_proof(
+        &self,
+        deps: Deps,
+        verifier: &VerifierConfig,
+        input: &VerificationInput,
+        proof: &str,
+    ) -> StdResult<bool> {
+        match verifier {
+            VerifierConfig::Contract { address } => {
+                let res: VerifyProofResponse = deps.querier.query_wasm_smart(
+                    address,
+                    &VerifierQueryMsg::VerifyProof {
+                        prompt: input.prompt.clone(),
+                        input: input.input.clone(),
+                        seed: input.seed,
+                        output: input.output.clone(),
+                        proof: proof.to_string(),
+                    },
+                )?;
+                Ok(res.valid)
+            }
+            VerifierConfig::Oracle {} => {
+                let attestation: Attestation = from_json(proof.as_bytes())?;
+                self.verify_attestation(deps, input, &attestation)
+            }
+            VerifierConfig::Groth16 {} => {
+                let vk = self
+                    .verifying_key
+                    .may_load(deps.storage)?
+                    .ok_or_else(|| StdError::generic_err("verifying key is not set."))?;
+                verify_groth16(&vk, input, &Binary::from_base64(proof)?)
+            }
+        }
+    }
+
+    /// Check an oracle signature over the canonical hash of `input`
+    pub fn verify_attestation(
+        &self,
+        deps: Deps,
+        input: &VerificationInput,
+        attestation: &Attestation,
+    ) -> StdResult<bool> {
+        let oracle = deps.api.addr_validate(&attestation.oracle)?;
+        let oracle = self
+            .oracles
+            .may_load(deps.storage, &oracle)?
+            .filter(|oracle| oracle.active)
+            .ok_or_else(|| StdError::generic_err("oracle is not active."))?;
+        let key = oracle
+            .metadata
+            .key
+            .ok_or_else(|| StdError::generic_err("oracle key is not registered."))?;
+
+        let hash = input.hash();
+        let valid = match key.scheme {
+            SignatureScheme::Secp256k1 => {
+                deps.api
+                    .secp256k1_verify(&hash, &attestation.signature, &key.pubkey)
+            }
+            SignatureScheme::Ed25519 => {
+                deps.api
+                    .ed25519_verify(&hash, &attestation.signature, &key.pubkey)
+            }
+        }
+        .map_err(|err| StdError::generic_err(err.to_string()))?;
+        Ok(valid)
+    }
+}